@@ -22,13 +22,13 @@ fn main() {
                 between.sample(&mut rng),
                 between.sample(&mut rng),
             );
-            vector.push_back(color.clone());
+            vector.push_back(color);
             vec.push(color);
         }
         (vec, vector)
     };
 
-    let window = WindowDesc::new(grid_ui);
+    let window = WindowDesc::new(grid_ui());
     AppLauncher::with_window(window)
         .launch(AppState {
             // colors: Arc::new(data),
@@ -1,24 +1,367 @@
 //! A basic grid view widget.
 
-use std::{cmp::Ordering, sync::Arc};
+use std::{
+    cmp::Ordering, collections::HashMap, f64::consts::PI, rc::Rc, sync::Arc,
+    time::Duration,
+};
 
-use druid::im::Vector;
+use druid::commands::SCROLL_TO_VIEW;
+use druid::im::{OrdMap, OrdSet, Vector};
+use druid::kurbo::{BezPath, Circle, ParamCurve, ParamCurveArclen, PathSeg};
 
 use druid::{
-    widget::Axis, BoxConstraints, Data, Env, KeyOrValue, LifeCycle, Point,
-    Rect, Size, Widget, WidgetPod,
+    theme, widget::Axis, Affine, BoxConstraints, Color, Command, Data, Env,
+    Event, EventCtx, KbKey, Key, KeyEvent, KeyOrValue, LayoutCtx, Lens,
+    LifeCycle, LinearGradient, Notification, Point, Rect, RenderContext,
+    Selector, Size, Target, TimerToken, UnitPoint, Vec2, Widget, WidgetId,
+    WidgetPod,
 };
 
+/// Command that makes a grid scroll its `Scroll` to an offset. Send it to the
+/// grid's `WidgetId` to restore a position saved with
+/// [`GridView::with_scroll_offset_sink`].
+pub const SET_SCROLL_OFFSET: Selector<Vec2> =
+    Selector::new("druid-gridview.set-scroll-offset");
+
+/// Command a grid built with [`GridView::with_content_metrics`] sends whenever
+/// its size or the part of it that is visible changes, so a scrollbar next to
+/// the grid can size and place its thumb without being the `Scroll` that holds
+/// the grid.
+pub const CONTENT_METRICS: Selector<ContentMetrics> =
+    Selector::new("druid-gridview.content-metrics");
+
+/// The size of a grid and the part of it that is visible, sent with
+/// [`CONTENT_METRICS`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentMetrics {
+    /// The size of the whole grid.
+    pub content_size: Size,
+    /// The size of the visible part of the grid.
+    pub viewport_size: Size,
+    /// How far the visible part is from the start of the grid.
+    pub scroll_offset: Vec2,
+}
+
+/// Command sent when the mouse has rested on a grid item for the tooltip delay.
+///
+/// The payload is the index of the item and the mouse position in window
+/// coordinates.
+pub const SHOW_TOOLTIP: Selector<(usize, Point)> =
+    Selector::new("druid-gridview.show-tooltip");
+
+/// Command sent when the mouse leaves the item whose tooltip was shown.
+pub const HIDE_TOOLTIP: Selector = Selector::new("druid-gridview.hide-tooltip");
+
+/// Command sent when items are added to or removed from a grid built with
+/// [`GridView::with_live_announcements`], with a message like "3 items added".
+///
+/// Druid doesn't talk to screen readers itself, so the application has to pass
+/// the message on to the platform's accessibility API.
+pub const LIVE_ANNOUNCEMENT: Selector<String> =
+    Selector::new("druid-gridview.live-announcement");
+
+/// Env key the application can set from the platform's accessibility settings
+/// when the user asks for less motion.
+///
+/// Grids built with [`GridView::with_respect_reduced_motion`] skip their
+/// animations while this is `true`.
+pub const REDUCED_MOTION: Key<bool> = Key::new("druid-gridview.reduced-motion");
+
+/// Command a grid sends itself to do the work found during layout that needs an
+/// [`EventCtx`].
+const RUN_DEFERRED: Selector = Selector::new("druid-gridview.run-deferred");
+
+/// The state of a grid item, handed to the item through the env key set with
+/// [`GridView::with_cell_state_env`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellState {
+    /// Whether the item is in the grid's selection.
+    pub selected: bool,
+    /// Whether the item has the grid's keyboard focus.
+    pub focused: bool,
+    /// Whether the mouse is over the item.
+    pub hovered: bool,
+    /// Whether [`GridIter::is_disabled`] says the item is disabled.
+    pub disabled: bool,
+}
+
+impl CellState {
+    const SELECTED: u64 = 1;
+    const FOCUSED: u64 = 1 << 1;
+    const HOVERED: u64 = 1 << 2;
+    const DISABLED: u64 = 1 << 3;
+
+    /// Pack the state into a value that can be stored in the env.
+    pub fn to_bits(self) -> u64 {
+        [
+            (self.selected, Self::SELECTED),
+            (self.focused, Self::FOCUSED),
+            (self.hovered, Self::HOVERED),
+            (self.disabled, Self::DISABLED),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |bits, (_, flag)| bits | flag)
+    }
+
+    /// Unpack a state packed with [`to_bits`](CellState::to_bits).
+    pub fn from_bits(bits: u64) -> Self {
+        CellState {
+            selected: bits & Self::SELECTED != 0,
+            focused: bits & Self::FOCUSED != 0,
+            hovered: bits & Self::HOVERED != 0,
+            disabled: bits & Self::DISABLED != 0,
+        }
+    }
+
+    /// Read the state stored under `key`, or the default state if there is none.
+    pub fn from_env(env: &Env, key: &Key<u64>) -> Self {
+        env.try_get(key).map(Self::from_bits).unwrap_or_default()
+    }
+}
+
+/// How far the mouse has to travel while pressed before a drag starts.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// How blurry the shadow under an item lifted by a drag is.
+const LIFT_BLUR: f64 = 8.0;
+
+/// How new items move into place as they appear, set with
+/// [`GridView::with_entrance`]. Items fade in whichever way they move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entrance {
+    /// Items fade in where they are.
+    Fade,
+    /// Items slide down into place from above.
+    FromTop,
+    /// Items slide up into place from below.
+    FromBottom,
+    /// Items slide right into place from the left.
+    FromLeft,
+    /// Items slide left into place from the right.
+    FromRight,
+    /// Items grow into place from their center.
+    Scale,
+}
+
+impl Entrance {
+    /// The transform of an item laid out at `rect`, `progress` of the way
+    /// through its entrance.
+    fn transform(self, rect: Rect, progress: f64) -> Affine {
+        // ease out, so items slow down as they arrive
+        let left = (1. - progress.clamp(0., 1.)).powi(2);
+        let (width, height) = (rect.width(), rect.height());
+        match self {
+            Entrance::Fade => Affine::IDENTITY,
+            Entrance::FromTop => Affine::translate((0., -height * left)),
+            Entrance::FromBottom => Affine::translate((0., height * left)),
+            Entrance::FromLeft => Affine::translate((-width * left, 0.)),
+            Entrance::FromRight => Affine::translate((width * left, 0.)),
+            Entrance::Scale => {
+                let center = rect.center().to_vec2();
+                Affine::translate(center)
+                    * Affine::scale(1. - left)
+                    * Affine::translate(-center)
+            }
+        }
+    }
+}
+
+/// How long, in seconds, an item takes to fade in with a staggered entrance.
+const APPEAR_DURATION: f64 = 0.25;
+
+/// How long, in seconds, the ripple over an activated item takes to spread.
+const RIPPLE_DURATION: f64 = 0.4;
+
+/// How deep the shadows along the overflowing edges of the visible area are.
+const SCROLL_SHADOW_DEPTH: f64 = 6.0;
+
+/// How far from a column border the mouse can be to start resizing the columns.
+const RESIZE_GRIP: f64 = 4.0;
+
+/// How close measured distances along a path have to be to the real ones.
+const ARCLEN_ACCURACY: f64 = 1e-3;
+
 /// A grid view widget for a variable size collection of items.
-pub struct GridView<T> {
+///
+/// `T` is the type of the items and `D` the type of the data the grid is used
+/// with, which holds the items.
+pub struct GridView<T, D> {
     closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
     children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    builders: HashMap<u32, ItemBuilder<T>>,
+    kinds: Vec<u32>,
+    cache: Vec<(u32, ChildPod<T>)>,
+    cache_capacity: usize,
     axis: Axis,
+    arrangement: Arrangement,
     vertical_spacing: KeyOrValue<f64>,
     horizontal_spacing: KeyOrValue<f64>,
     minor_axis_count: MinorAxisCount,
+    cell_clip: bool,
+    row_stripes: Option<KeyOrValue<Color>>,
+    rows: Vec<(f64, f64)>,
+    drop_target_highlight: Option<KeyOrValue<Color>>,
+    drag_lift: Option<KeyOrValue<Color>>,
+    ripple_color: Option<KeyOrValue<Color>>,
+    ripple: Option<Ripple>,
+    drag: Option<Drag>,
+    on_reorder_complete: Option<ReorderCallback>,
+    initial_scroll: Option<usize>,
+    scroll_request: Option<Rect>,
+    offset_request: Option<Vec2>,
+    scroll_offset_sink: Option<ScrollOffsetSink<D>>,
+    offset_written: Vec2,
+    visible: Rect,
+    viewport: Rect,
+    edge_fade: f64,
+    scroll_shadows: Option<KeyOrValue<Color>>,
+    deferred: bool,
+    layout_budget: Option<usize>,
+    laid_out: usize,
+    pending_bc: Option<BoxConstraints>,
+    incremental_pass: bool,
+    section_header: Option<HeaderBuilder>,
+    headers: Vec<(SectionId, HeaderPod)>,
+    repaint_on_hover: bool,
+    cell_state_key: Option<Key<u64>>,
+    cell_envs: Rc<CellEnvs>,
+    respect_reduced_motion: bool,
+    live_announcements: bool,
+    metrics_target: Option<Target>,
+    metrics_sent: Option<ContentMetrics>,
+    stagger: Option<Duration>,
+    entrance: Option<Entrance>,
+    appear: Vec<f64>,
+    hovered: Option<usize>,
+    min_touch_target: Size,
+    tooltip_delay: Option<Duration>,
+    tooltip_timer: Option<(TimerToken, usize, Point)>,
+    tooltip_shown: Option<usize>,
+    snap_to_rows: bool,
+    column_weights: Option<Vec<f64>>,
+    tracks: Vec<f64>,
+    resizing: Option<(usize, f64)>,
+    on_column_resize: Option<ColumnResizeCallback>,
+    intrinsic_width: bool,
+    trailing_anchor: bool,
+    ratio_range: Option<(f64, f64)>,
+    cell_size: Size,
+    min_wrap_gap: f64,
+    column_count_sink: Option<ColumnCountSink<D>>,
+    column_count: usize,
+    longest_line: usize,
+    column_count_written: bool,
+    cell_margin: KeyOrValue<f64>,
+    collapse_margins: bool,
+    marquee_selection: bool,
+    marquee: Option<(Point, Point)>,
+    selection: Vec<usize>,
+    on_selection_change: Option<SelectionCallback>,
+    ordered_selection: Option<SelectionLens<D>>,
+    long_press: Option<Duration>,
+    long_press_timer: Option<(TimerToken, usize, Point)>,
+    selection_mode: bool,
+    focused: Option<usize>,
+    selection_follows_focus: bool,
+    disabled: Vec<bool>,
+    fade_color: KeyOrValue<Color>,
+    on_cell_key: Option<CellKeyCallback<T>>,
+    on_item_click: Option<ItemClickCallback<T>>,
+    pressed: Option<usize>,
+    z_order: Option<ZOrderFn>,
+    aggregate_builder: Option<AggregateBuilder<D>>,
+    aggregate_row: Option<AggregatePod<D>>,
+    aggregate_shift: Vec2,
+}
+
+type ItemBuilder<T> = Box<dyn Fn() -> Box<dyn Widget<T>>>;
+type ChildPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+type ReorderCallback = Box<dyn Fn(&mut EventCtx, usize, usize)>;
+type SelectionCallback = Box<dyn Fn(&mut EventCtx, &[usize])>;
+type ColumnResizeCallback = Box<dyn Fn(&mut EventCtx, &[f64])>;
+type CellKeyCallback<T> = Box<dyn Fn(&mut EventCtx, &KeyEvent, &mut T, usize)>;
+type ItemClickCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, usize)>;
+type AggregateBuilder<D> = Box<dyn Fn(&D) -> Box<dyn Widget<D>>>;
+type AggregatePod<D> = WidgetPod<D, Box<dyn Widget<D>>>;
+
+/// A lens from the data of the grid to the picked items.
+struct SelectionLens<D> {
+    get: SelectionGetter<D>,
+    put: SelectionSetter<D>,
+}
+
+type SelectionGetter<D> = Box<dyn Fn(&D) -> OrdSet<usize>>;
+type SelectionSetter<D> = Box<dyn Fn(&mut D, OrdSet<usize>)>;
+type ColumnCountSink<D> = Box<dyn Fn(&mut D, usize)>;
+type ScrollOffsetSink<D> = Box<dyn Fn(&mut D, Vec2)>;
+
+/// A ripple spreading over an item that was just activated.
+struct Ripple {
+    /// The index of the activated item.
+    idx: usize,
+    /// Where the ripple starts, in the grid's coordinates.
+    center: Point,
+    /// The time since the ripple started.
+    t: f64,
+}
+
+/// The state of a mouse drag that started on a grid item.
+struct Drag {
+    /// The index of the item the drag started on.
+    source: usize,
+    /// Where the mouse was pressed.
+    start: Point,
+    /// Where the mouse is now.
+    pos: Point,
+    /// Whether the mouse has moved far enough for this to count as a drag.
+    active: bool,
+    /// The item currently under the mouse.
+    target: Option<usize>,
+    /// Whether the number of items changed during the drag, so it no longer
+    /// moves anything and only waits for the mouse to be released.
+    cancelled: bool,
+}
+
+/// The env of each item with its [`CellState`] added.
+///
+/// Most items are in the default state, so they share one env and only the
+/// others get an env of their own. The grid keeps the envs until its own env or
+/// the state of an item changes.
+#[derive(Default)]
+struct CellEnvs {
+    /// The env the others were made from.
+    base: Option<Env>,
+    /// The states of the items that aren't in the default state, by index.
+    states: HashMap<usize, CellState>,
+    /// The env of the items in the default state, if there is a state key.
+    default: Option<Env>,
+    /// The envs of the items in any other state, by index.
+    envs: HashMap<usize, Env>,
+}
+
+impl CellEnvs {
+    /// The env for the item at `idx`, or `env` if there is no state key.
+    fn get<'a>(&'a self, idx: usize, env: &'a Env) -> &'a Env {
+        self.envs.get(&idx).or(self.default.as_ref()).unwrap_or(env)
+    }
 }
 
+/// How the grid items are arranged.
+enum Arrangement {
+    /// Rows or columns that wrap along the minor axis.
+    Grid,
+    /// Evenly spaced around a circle, starting at `start_angle` radians.
+    Radial { radius: f64, start_angle: f64 },
+    /// Wherever a user supplied function puts them.
+    Custom(PlacementFn),
+    /// Evenly spaced along a path.
+    Path(BezPath),
+}
+
+type PlacementFn = Box<dyn Fn(usize, usize, Size) -> Point>;
+type ZOrderFn = Box<dyn Fn(usize) -> i32>;
+
 /// The number of elements found on the minor axis of the grid
 enum MinorAxisCount {
     /// If this is wrap, the grid determines the max amount of items per
@@ -29,7 +372,7 @@ enum MinorAxisCount {
     Count(u64), // this should probably take a KeyOrValue<u64> instead
 }
 
-impl<T: Data> GridView<T> {
+impl<T: Data, D> GridView<T, D> {
     /// Create a new grid view widget. The closure will be called when a new item needs
     /// to be constructed.
     ///
@@ -41,10 +384,90 @@ impl<T: Data> GridView<T> {
         GridView {
             closure: Box::new(move || Box::new(closure())),
             children: Vec::new(),
+            builders: HashMap::new(),
+            kinds: Vec::new(),
+            cache: Vec::new(),
+            cache_capacity: 0,
             axis: Axis::Vertical,
+            arrangement: Arrangement::Grid,
             vertical_spacing: KeyOrValue::Concrete(0.),
             horizontal_spacing: KeyOrValue::Concrete(0.),
             minor_axis_count: MinorAxisCount::Count(5),
+            cell_clip: false,
+            row_stripes: None,
+            rows: Vec::new(),
+            drop_target_highlight: None,
+            drag_lift: None,
+            ripple_color: None,
+            ripple: None,
+            drag: None,
+            on_reorder_complete: None,
+            initial_scroll: None,
+            scroll_request: None,
+            offset_request: None,
+            scroll_offset_sink: None,
+            offset_written: Vec2::ZERO,
+            visible: Rect::ZERO,
+            viewport: Rect::ZERO,
+            edge_fade: 0.,
+            scroll_shadows: None,
+            deferred: false,
+            layout_budget: None,
+            laid_out: 0,
+            pending_bc: None,
+            incremental_pass: false,
+            section_header: None,
+            headers: Vec::new(),
+            repaint_on_hover: false,
+            cell_state_key: None,
+            cell_envs: Rc::default(),
+            respect_reduced_motion: false,
+            live_announcements: false,
+            metrics_target: None,
+            metrics_sent: None,
+            stagger: None,
+            entrance: None,
+            appear: Vec::new(),
+            hovered: None,
+            min_touch_target: Size::ZERO,
+            tooltip_delay: None,
+            tooltip_timer: None,
+            tooltip_shown: None,
+            snap_to_rows: false,
+            column_weights: None,
+            tracks: Vec::new(),
+            resizing: None,
+            on_column_resize: None,
+            intrinsic_width: false,
+            trailing_anchor: false,
+            ratio_range: None,
+            cell_size: Size::ZERO,
+            min_wrap_gap: 0.,
+            column_count_sink: None,
+            column_count: 0,
+            longest_line: 0,
+            column_count_written: false,
+            cell_margin: KeyOrValue::Concrete(0.),
+            collapse_margins: false,
+            marquee_selection: false,
+            marquee: None,
+            selection: Vec::new(),
+            on_selection_change: None,
+            ordered_selection: None,
+            long_press: None,
+            long_press_timer: None,
+            selection_mode: false,
+            focused: None,
+            selection_follows_focus: false,
+            disabled: Vec::new(),
+            fade_color: theme::WINDOW_BACKGROUND_COLOR.into(),
+            on_cell_key: None,
+            on_item_click: None,
+            pressed: None,
+            z_order: None,
+            aggregate_builder: None,
+            aggregate_row: None,
+            aggregate_shift: Vec2::ZERO,
         }
     }
 
@@ -54,6 +477,57 @@ impl<T: Data> GridView<T> {
         self
     }
 
+    /// Builder style method that lays the items out evenly around a circle
+    /// instead of in rows/columns, with the center of each item on the circle.
+    ///
+    /// The first item is placed at `start_angle`, in radians clockwise from the
+    /// positive x axis, and the rest follow clockwise. Spacing, the axis and
+    /// section headers don't apply to this layout.
+    pub fn with_radial(mut self, radius: f64, start_angle: f64) -> Self {
+        self.arrangement = Arrangement::Radial {
+            radius,
+            start_angle,
+        };
+        self
+    }
+
+    /// Builder style method that places each item at the origin returned by
+    /// `place` instead of in rows/columns.
+    ///
+    /// `place` receives the index of the item, the total number of items and the
+    /// size of the item. Spacing, the axis and section headers don't apply to this
+    /// layout.
+    pub fn with_layout_fn(
+        mut self,
+        place: impl Fn(usize, usize, Size) -> Point + 'static,
+    ) -> Self {
+        self.arrangement = Arrangement::Custom(Box::new(place));
+        self
+    }
+
+    /// Builder style method to paint items in order of the z value returned by
+    /// `z_order` for their index, so items with a higher z are painted on top.
+    ///
+    /// Items with the same z are painted in data order, which is also the order
+    /// used when this isn't set.
+    pub fn with_cell_z_order(
+        mut self,
+        z_order: impl Fn(usize) -> i32 + 'static,
+    ) -> Self {
+        self.z_order = Some(Box::new(z_order));
+        self
+    }
+
+    /// Builder style method that centers the items on `path`, evenly spaced by
+    /// distance along it, instead of in rows/columns.
+    ///
+    /// The first item goes at the start of the path and the last at its end.
+    /// Spacing, the axis and section headers don't apply to this layout.
+    pub fn with_path_layout(mut self, path: BezPath) -> Self {
+        self.arrangement = Arrangement::Path(path);
+        self
+    }
+
     /// This will allow the grid to automatically determine how many items
     /// can be laid out on the minor axis before wrapping.
     ///
@@ -63,6 +537,14 @@ impl<T: Data> GridView<T> {
         self
     }
 
+    /// Builder style method that makes [`wrap`] leave at least `gap` of unused
+    /// space on the minor axis, putting one less item in each row/column when the
+    /// items would otherwise be packed against the edge.
+    pub fn with_min_gap_on_wrap(mut self, gap: f64) -> Self {
+        self.min_wrap_gap = gap;
+        self
+    }
+
     /// Builder style method that sets how many elements will be laid out on the
     /// minor axis before the grid wraps around to the next row/column.
     ///
@@ -83,6 +565,127 @@ impl<T: Data> GridView<T> {
         self
     }
 
+    /// Builder style method that writes the number of items the grid fits on the
+    /// minor axis into its data through `lens` whenever a layout changes it, for
+    /// example after the grid was resized in [`wrap`] mode.
+    ///
+    /// The count is written after the layout, and only when it changed, so the
+    /// update it causes doesn't cause another write. With [`with_intrinsic_width`] it is the
+    /// number of items on the fullest row/column. Layouts other than
+    /// rows/columns write zero.
+    ///
+    /// [`with_intrinsic_width`]: GridView::with_intrinsic_width
+    pub fn with_column_count_sink(
+        mut self,
+        lens: impl Lens<D, usize> + 'static,
+    ) -> Self {
+        self.column_count_sink = Some(Box::new(move |data, count| {
+            lens.with_mut(data, |old| *old = count)
+        }));
+        self
+    }
+
+    /// Builder style method that lets each item keep its own size on the minor
+    /// axis, moving on to the next row/column only when the next item doesn't
+    /// fit, like a flex-wrap or tag cloud layout.
+    ///
+    /// This replaces the fixed item count of [`wrap`], [`with_minor_axis_count`]
+    /// and [`with_weighted_columns`].
+    pub fn with_intrinsic_width(mut self, intrinsic: bool) -> Self {
+        self.intrinsic_width = intrinsic;
+        self
+    }
+
+    /// Builder style method that sets the margin around each grid item. This is
+    /// added to the spacing between items and also separates the items from the
+    /// edges of the grid.
+    pub fn with_cell_margin(
+        mut self,
+        margin: impl Into<KeyOrValue<f64>>,
+    ) -> Self {
+        self.cell_margin = margin.into();
+        self
+    }
+
+    /// Builder style method that makes the margins of neighboring items overlap
+    /// instead of adding up, so two items with a 5px margin are 5px apart rather
+    /// than 10px.
+    pub fn with_cell_margin_collapse(mut self, collapse: bool) -> Self {
+        self.collapse_margins = collapse;
+        self
+    }
+
+    /// Builder style method that splits the minor axis into one track per weight,
+    /// with each track getting space in proportion to its weight. For example
+    /// `vec![1.0, 2.0, 1.0]` makes the middle column twice as wide as the others.
+    ///
+    /// Items are constrained to the size of their track. This takes priority over
+    /// [`wrap`] and [`with_minor_axis_count`], but is ignored when the minor axis is
+    /// unbounded.
+    pub fn with_weighted_columns(mut self, weights: Vec<f64>) -> Self {
+        self.column_weights = Some(weights);
+        self
+    }
+
+    /// Builder style method that lets the user resize the columns from
+    /// [`with_weighted_columns`] by dragging the borders between them.
+    ///
+    /// When a drag ends `cb` receives the new weights, which are the sizes of the
+    /// columns at that point.
+    pub fn on_column_resize(
+        mut self,
+        cb: impl Fn(&mut EventCtx, &[f64]) + 'static,
+    ) -> Self {
+        self.on_column_resize = Some(Box::new(cb));
+        self
+    }
+
+    /// Builder style method that lays out at most `budget` new items per layout
+    /// pass and asks for more passes until all of them are laid out, so loading a
+    /// very large grid doesn't hold up a single frame.
+    ///
+    /// Items waiting for their turn take no space and can't be clicked, focused
+    /// or scrolled to yet. The passes the grid asks for itself leave the items it
+    /// has already placed where they are, unless they ask for a layout. Only
+    /// rows/columns are laid out incrementally.
+    pub fn with_incremental_layout(mut self, budget: usize) -> Self {
+        self.layout_budget = Some(budget.max(1));
+        self
+    }
+
+    /// Builder style method that keeps the aspect ratio, width divided by height,
+    /// of items in rows/columns between `min_ratio` and `max_ratio`.
+    ///
+    /// Items keep their size on the minor axis, like the width of their column in
+    /// a vertical grid, and are laid out again with their major size clamped when
+    /// they come out too wide or too tall.
+    ///
+    /// Panics if either ratio isn't greater than zero.
+    pub fn with_cell_ratio_range(
+        mut self,
+        min_ratio: f64,
+        max_ratio: f64,
+    ) -> Self {
+        assert!(
+            min_ratio > 0. && max_ratio > 0.,
+            "cell aspect ratios have to be greater than zero"
+        );
+        self.ratio_range =
+            Some((min_ratio.min(max_ratio), max_ratio.max(min_ratio)));
+        self
+    }
+
+    /// Builder style method that pushes each row/column against the trailing
+    /// edge of the minor axis, the right edge in a vertical grid, so the leftover
+    /// space ends up at the start.
+    ///
+    /// Items keep their order and spacing. This has no effect when the minor axis
+    /// is unbounded.
+    pub fn with_trailing_anchor(mut self, anchor: bool) -> Self {
+        self.trailing_anchor = anchor;
+        self
+    }
+
     /// Builder style method that sets the vertical and horizontal spacing
     /// between elements to the same value.
     pub fn with_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
@@ -139,203 +742,654 @@ impl<T: Data> GridView<T> {
         self
     }
 
-    /// When the widget is created or the data changes, create or remove children as needed
+    /// Builder style method that clips the paint of each grid item to its own
+    /// cell, so children that paint outside of their bounds don't spill into
+    /// their neighbors.
+    pub fn with_cell_clip(mut self, clip: bool) -> Self {
+        self.cell_clip = clip;
+        self
+    }
+
+    /// Builder style method that paints every other row/column with `color`
+    /// behind the items, starting with the second.
     ///
-    /// Returns `true` if children were added or removed.
-    fn update_child_count(
-        &mut self,
-        data: &impl GridIter<T>,
-        _env: &Env,
-    ) -> bool {
-        let len = self.children.len();
-        match len.cmp(&data.data_len()) {
-            Ordering::Greater => self.children.truncate(data.data_len()),
-            Ordering::Less => data.for_each(|_, i| {
-                if i >= len {
-                    let child = WidgetPod::new((self.closure)());
-                    self.children.push(child);
-                }
-            }),
-            Ordering::Equal => (),
-        }
-        len != data.data_len()
+    /// Stripes follow the position of a row/column in the whole grid, so they
+    /// don't change when the grid scrolls. Only rows/columns are striped.
+    pub fn with_row_stripes(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.row_stripes = Some(color.into());
+        self
     }
-}
 
-/// This iterator enables writing GridView widget for any `Data`.
-pub trait GridIter<T>: Data {
-    /// Iterate over each data child.
-    fn for_each(&self, cb: impl FnMut(&T, usize));
+    /// Builder style method that allows grid items to be dragged to reorder
+    /// them, tinting the item under the mouse with `color` while a drag is in
+    /// progress.
+    ///
+    /// The tint is painted over the item, so this should usually be a translucent color.
+    pub fn with_drop_target_highlight(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.drop_target_highlight = Some(color.into());
+        self
+    }
 
-    /// Iterate over each data child and keep track of changed data to update self.
-    fn for_each_mut(&mut self, cb: impl FnMut(&mut T, usize));
+    /// Builder style method that allows grid items to be dragged to reorder
+    /// them, lifting the dragged item off the grid and painting it under the
+    /// mouse with a shadow of `shadow`. The items it passes move aside to open a
+    /// gap where it would be dropped.
+    pub fn with_drag_lift(
+        mut self,
+        shadow: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.drag_lift = Some(shadow.into());
+        self
+    }
 
-    /// Return the data length.
-    fn data_len(&self) -> usize;
+    /// Builder style method that spreads a ripple of `color` over an item when
+    /// it's pressed, from where the mouse went down, or activated with Enter or
+    /// Space, from the center of the item.
+    ///
+    /// The grid takes keyboard focus when an item is clicked, so the arrow keys
+    /// can move to another item to activate. The ripple is painted over the
+    /// item, so this should usually be a translucent color. It isn't shown while
+    /// [`REDUCED_MOTION`] is respected.
+    pub fn with_cell_ripple(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.ripple_color = Some(color.into());
+        self
+    }
 
-    /// Return any child data to be used to get child size.
-    fn child_data(&self) -> Option<T>;
+    /// Builder style method that allows grid items to be dragged to reorder them,
+    /// and sets a callback that receives the old and new index of an item once it
+    /// has been dropped and moved in the data.
+    ///
+    /// The data is reordered with [`GridIter::move_item`], and the callback is
+    /// only called if it actually moved the item. Selection, focus and the item
+    /// widgets move along with the item.
+    pub fn on_reorder_complete(
+        mut self,
+        cb: impl Fn(&mut EventCtx, usize, usize) + 'static,
+    ) -> Self {
+        self.on_reorder_complete = Some(Box::new(cb));
+        self
+    }
 
-    // fn row(&self, cb: impl FnMut(&T, usize), row_len: usize);
-    // fn row_mut(&mut self, cb: impl FnMut(&mut T, usize), row_len: usize);
-}
+    /// Builder style method that allows selecting items by dragging a rectangle
+    /// over them.
+    pub fn with_marquee_selection(mut self, marquee: bool) -> Self {
+        self.marquee_selection = marquee;
+        self
+    }
 
-impl<T: Data> GridIter<T> for Arc<Vec<T>> {
-    // fn row(&self, mut cb: impl FnMut(&T, usize), row_len: usize) {
-    //     let chunks_len = row_len;
-    //     for (i, row) in self.chunks(chunks_len).enumerate() {
-    //         for (j, item) in row.iter().enumerate() {
-    //             cb(item, i * chunks_len + j)
-    //         }
-    //     }
-    // }
-    // fn row_mut(&mut self, mut cb: impl FnMut(&mut T, usize), row_len: usize) {
-    //     let chunks_len = row_len;
-    //     let mut new_data = Vec::with_capacity(self.data_len());
-    //     let mut any_changed = false;
+    /// Builder style method that sets a callback which receives the indices of
+    /// the selected items whenever the selection changes.
+    pub fn on_selection_change(
+        mut self,
+        cb: impl Fn(&mut EventCtx, &[usize]) + 'static,
+    ) -> Self {
+        self.on_selection_change = Some(Box::new(cb));
+        self
+    }
 
-    //     for (i, row) in self.chunks(chunks_len).enumerate() {
-    //         for (j, item) in row.iter().enumerate() {
-    //             let mut d = item.to_owned();
-    //             cb(&mut d, i * chunks_len + j);
+    /// Builder style method that lets the user pick items by clicking them and
+    /// keeps the picked indices in the data of the grid through `lens`.
+    ///
+    /// Clicking a picked item removes it again. Picking takes the click, so drags
+    /// and marquees have to start between items.
+    ///
+    /// The set is sorted by index, so the grid keeps the order the items were
+    /// picked in itself and passes it to [`on_selection_change`]. Indices the
+    /// data adds to the set go after those already picked, in index order.
+    ///
+    /// [`on_selection_change`]: GridView::on_selection_change
+    pub fn with_ordered_selection(
+        mut self,
+        lens: impl Lens<D, OrdSet<usize>> + 'static,
+    ) -> Self {
+        let lens = Rc::new(lens);
+        let put_lens = lens.clone();
+        self.ordered_selection = Some(SelectionLens {
+            get: Box::new(move |data| lens.with(data, |picked| picked.clone())),
+            put: Box::new(move |data, picked| {
+                put_lens.with_mut(data, |old| *old = picked)
+            }),
+        });
+        self
+    }
 
-    //             if !any_changed && !item.same(&d) {
-    //                 any_changed = true;
-    //             }
-    //             new_data.push(d);
-    //         }
-    //     }
+    /// Builder style method that enters selection mode when an item is pressed
+    /// for `delay` without moving, as with a long-press on a touch screen, and
+    /// selects that item.
+    ///
+    /// While in selection mode, pressing an item adds it to the selection or
+    /// removes it again instead of clicking it. Selection mode ends when the
+    /// last item is removed from the selection.
+    pub fn with_long_press_selection(mut self, delay: Duration) -> Self {
+        self.long_press = Some(delay);
+        self
+    }
 
-    //     if any_changed {
-    //         *self = Arc::new(new_data);
-    //     }
-    // }
+    /// Whether a long-press put the grid in selection mode, see
+    /// [`GridView::with_long_press_selection`].
+    pub fn in_selection_mode(&self) -> bool {
+        self.selection_mode
+    }
 
-    fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
-        for (i, item) in self.iter().enumerate() {
-            cb(item, i);
-        }
+    /// Builder style method that sets a callback for key presses while the grid
+    /// has focus. The callback receives the focused item and its index.
+    ///
+    /// The grid takes focus when it is clicked, and the clicked item becomes the
+    /// focused one. The callback runs before Enter or Space activate the item for
+    /// [`on_item_click`], and calling `ctx.set_handled()` in it stops that.
+    ///
+    /// [`on_item_click`]: GridView::on_item_click
+    pub fn on_cell_key(
+        mut self,
+        cb: impl Fn(&mut EventCtx, &KeyEvent, &mut T, usize) + 'static,
+    ) -> Self {
+        self.on_cell_key = Some(Box::new(cb));
+        self
     }
 
-    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut T, usize)) {
-        let mut new_data = Vec::with_capacity(self.data_len());
-        let mut any_changed = false;
+    /// Builder style method that makes the focused item the only selected item
+    /// whenever focus moves with the arrow keys, instead of leaving the selection
+    /// as it is.
+    ///
+    /// This also makes the grid take focus when it is clicked.
+    pub fn with_selection_follows_focus(mut self, follows: bool) -> Self {
+        self.selection_follows_focus = follows;
+        self
+    }
 
-        for (i, item) in self.iter().enumerate() {
-            let mut d = item.to_owned();
-            cb(&mut d, i);
+    /// Builder style method that sets a callback for when an item is clicked, or
+    /// activated with Enter or Space while it is focused. The callback receives
+    /// the item and its index.
+    ///
+    /// The grid takes focus when it is clicked, and the clicked item becomes the
+    /// focused one. Clicks an item handles itself, like on a button inside it,
+    /// and drags don't count. Neither do Enter and Space when the
+    /// [`on_cell_key`] callback handled them.
+    ///
+    /// [`on_cell_key`]: GridView::on_cell_key
+    pub fn on_item_click(
+        mut self,
+        cb: impl Fn(&mut EventCtx, &mut T, usize) + 'static,
+    ) -> Self {
+        self.on_item_click = Some(Box::new(cb));
+        self
+    }
 
-            if !any_changed && !item.same(&d) {
-                any_changed = true;
-            }
-            new_data.push(d);
-        }
+    /// Builder style method that makes the grid treat each item as at least
+    /// `size` large when finding the item under the mouse, so small items are
+    /// still easy to tap. Items aren't laid out or painted any larger.
+    ///
+    /// Where the grown areas of neighbouring items overlap, the item whose center
+    /// is closest wins. This is for the grid's own handling of clicks, selection,
+    /// drags and hovering, the item widgets still only get the mouse over their
+    /// own area.
+    pub fn with_min_touch_target(mut self, size: Size) -> Self {
+        self.min_touch_target = size;
+        self
+    }
 
-        if any_changed {
-            *self = Arc::new(new_data);
-        }
+    /// Builder style method that sends [`SHOW_TOOLTIP`] once the mouse has rested
+    /// on an item for `delay`, and [`HIDE_TOOLTIP`] when it leaves that item.
+    pub fn with_tooltip_delay(mut self, delay: Duration) -> Self {
+        self.tooltip_delay = Some(delay);
+        self
     }
 
-    fn data_len(&self) -> usize {
-        self.len()
+    /// Builder style method that scrolls the item at `index` into view the first
+    /// time it is laid out, by sending [`SCROLL_TO_VIEW`] to the enclosing
+    /// `Scroll`.
+    pub fn with_initial_scroll(mut self, index: usize) -> Self {
+        self.initial_scroll = Some(index);
+        self
     }
 
-    fn child_data(&self) -> Option<T> {
-        // TODO MUST FIX THIS - no panics
-        Some(self.iter().next().unwrap().clone())
+    /// Builder style method that fades new items in when they first appear, each
+    /// one starting `per_cell_delay` after the one before it.
+    ///
+    /// This applies to the items the grid starts with and to items added later.
+    /// Items fade in from the [`with_fade_color`] painted over them.
+    pub fn with_stagger(mut self, per_cell_delay: Duration) -> Self {
+        self.stagger = Some(per_cell_delay);
+        self
     }
-}
 
-impl<T: Data> GridIter<T> for Vector<T> {
-    fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
-        for (i, item) in self.iter().enumerate() {
-            cb(item, i);
+    /// Builder style method that animates new items into place the way
+    /// `entrance` says when they first appear.
+    ///
+    /// Without [`with_stagger`] all new items enter at once.
+    ///
+    /// [`with_stagger`]: GridView::with_stagger
+    pub fn with_entrance(mut self, entrance: Entrance) -> Self {
+        self.entrance = Some(entrance);
+        self
+    }
+
+    /// Builder style method that stores the [`CellState`] of each item under
+    /// `key` in the env the item gets, so it can draw itself differently when it
+    /// is selected, focused, hovered or disabled.
+    ///
+    /// The state is there for events, lifecycle, update and paint, and the item is
+    /// repainted when it changes. Items are laid out without it, so a change of
+    /// state never moves them.
+    pub fn with_cell_state_env(mut self, key: Key<u64>) -> Self {
+        self.cell_state_key = Some(key);
+        self
+    }
+
+    /// Builder style method that sends [`LIVE_ANNOUNCEMENT`] whenever the data
+    /// changes the number of items, for screen readers. Items whose widget is
+    /// built again because their [`GridIter::kind`] changed aren't announced.
+    pub fn with_live_announcements(mut self, announce: bool) -> Self {
+        self.live_announcements = announce;
+        self
+    }
+
+    /// Builder style method that sends [`CONTENT_METRICS`] to `target` whenever
+    /// the size of the grid or the part of it that is visible changes, like after
+    /// the enclosing `Scroll` scrolled.
+    ///
+    /// Nothing is sent before the visible part of the grid is known, which is
+    /// after its first layout.
+    pub fn with_content_metrics(mut self, target: impl Into<Target>) -> Self {
+        self.metrics_target = Some(target.into());
+        self
+    }
+
+    /// Builder style method that makes the grid skip its animations, with items
+    /// jumping straight to where they end up, while [`REDUCED_MOTION`] is set in
+    /// the env.
+    pub fn with_respect_reduced_motion(mut self, respect: bool) -> Self {
+        self.respect_reduced_motion = respect;
+        self
+    }
+
+    /// Whether the grid skips its animations in `env`.
+    pub fn reduces_motion(&self, env: &Env) -> bool {
+        self.respect_reduced_motion
+            && env.try_get(REDUCED_MOTION).unwrap_or(false)
+    }
+
+    /// Builder style method that sets the color painted over disabled items to
+    /// dim them, over items near the edges with [`with_edge_fade`], and over new
+    /// items as they fade in with [`with_stagger`] or [`with_entrance`].
+    ///
+    /// druid can't paint a widget with reduced opacity, so the items are faded
+    /// by painting this color over them with increasing opacity instead. It
+    /// should match whatever is behind the grid. The default is
+    /// [`theme::WINDOW_BACKGROUND_COLOR`].
+    pub fn with_fade_color(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.fade_color = color.into();
+        self
+    }
+
+    /// Builder style method that fades items into the background within `fade`
+    /// of the visible edges of a scroll container, where the grid continues past
+    /// the edge.
+    ///
+    /// The items aren't painted with reduced alpha, which druid doesn't support.
+    /// A gradient of the [`with_fade_color`] is painted over them instead.
+    pub fn with_edge_fade(mut self, fade: f64) -> Self {
+        self.edge_fade = fade;
+        self
+    }
+
+    /// Builder style method that paints shadows of `color` along the visible
+    /// edges of a scroll container where the grid continues past the edge, so a
+    /// shadow disappears once the grid is scrolled all the way to that end.
+    pub fn with_scroll_shadows(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.scroll_shadows = Some(color.into());
+        self
+    }
+
+    /// Builder style method that sets the closure used to build the header shown
+    /// at the start of each section, for data where [`GridIter::section`] returns
+    /// a section, like [`Sectioned`].
+    ///
+    /// Headers take up a whole row/column and each section starts on a new one.
+    pub fn with_section_header<W: Widget<SectionId> + 'static>(
+        mut self,
+        header: impl Fn(SectionId) -> W + 'static,
+    ) -> Self {
+        self.section_header = Some(Box::new(move |id| Box::new(header(id))));
+        self
+    }
+
+    /// Builder style method that keeps up to `capacity` widgets of removed items
+    /// around and reuses them for new items instead of calling the closure again.
+    ///
+    /// This helps when the item widgets are expensive to build and the data often
+    /// shrinks and grows again. A reused widget gets the data of its new item
+    /// through `update`. Widgets are only reused for items of the same
+    /// [`GridIter::kind`].
+    pub fn with_cell_builder_cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Builder style method that sets a closure per [`GridIter::kind`] to build
+    /// the widgets of items of that kind, for grids that mix different items.
+    ///
+    /// Items of kinds that aren't in `builders` use the closure the grid was
+    /// created with. When the kind of an item changes, its widget is built again.
+    pub fn with_builders(
+        mut self,
+        builders: HashMap<u32, ItemBuilder<T>>,
+    ) -> Self {
+        self.builders = builders;
+        self
+    }
+
+    /// Builder style method that adds a row after the last item for totals or
+    /// other summaries of the data.
+    ///
+    /// `build` makes the row from the grid's data when the grid is added to the
+    /// widget tree. The row is a widget of the same data as the grid, so changes
+    /// it makes go to the data like those of the items.
+    ///
+    /// It spans the minor axis and, when the grid continues past
+    /// the visible area of a scroll container, sticks to the end of that area.
+    /// Only rows/columns have an aggregate row.
+    pub fn with_aggregate_row(
+        mut self,
+        build: impl Fn(&D) -> Box<dyn Widget<D>> + 'static,
+    ) -> Self {
+        self.aggregate_builder = Some(Box::new(build));
+        self
+    }
+
+    /// Builder style method that shrinks the grid on the major axis to fit a whole
+    /// number of rows/columns, so a partially visible one doesn't peek out at the
+    /// end of a bounded container.
+    ///
+    /// The first row/column is used as the size of every row/column, and the
+    /// margins and the spacing between rows/columns are kept. At least one
+    /// row/column is always shown. This has no effect when the major axis is
+    /// unbounded.
+    pub fn with_snap_height_to_rows(mut self, snap: bool) -> Self {
+        self.snap_to_rows = snap;
+        self
+    }
+
+    /// Builder style method that repaints the items the mouse enters and leaves.
+    ///
+    /// Only the affected items are invalidated rather than the whole grid, which
+    /// keeps hover effects cheap in grids with many items.
+    pub fn with_repaint_on_hover(mut self, repaint: bool) -> Self {
+        self.repaint_on_hover = repaint;
+        self
+    }
+
+    /// Builder style method that scrolls the enclosing `Scroll` to `offset`
+    /// once the grid is laid out, to restore a position saved with
+    /// [`with_scroll_offset_sink`]. Send [`SET_SCROLL_OFFSET`] to restore a
+    /// position later on.
+    pub fn with_scroll_offset(mut self, offset: Vec2) -> Self {
+        self.offset_request = Some(offset);
+        self
+    }
+
+    /// Builder style method that writes how far the grid is scrolled within its
+    /// `Scroll` into its data through `lens`, so the position can be saved and
+    /// restored with [`with_scroll_offset`].
+    ///
+    /// The offset is written whenever the `Scroll` moves the grid or changes
+    /// size, once it moved away from where the grid started.
+    pub fn with_scroll_offset_sink(
+        mut self,
+        lens: impl Lens<D, Vec2> + 'static,
+    ) -> Self {
+        self.scroll_offset_sink = Some(Box::new(move |data, offset| {
+            lens.with_mut(data, |old| *old = offset)
+        }));
+        self
+    }
+
+    /// Work found outside of event handling that needs an [`EventCtx`] is done
+    /// when the grid receives [`RUN_DEFERRED`], which is sent to the grid with
+    /// `id` unless it is already on its way.
+    fn defer(&mut self, id: WidgetId) -> Option<Command> {
+        let deferred = std::mem::replace(&mut self.deferred, true);
+        (!deferred).then(|| RUN_DEFERRED.to(id))
+    }
+
+    /// Run the work queued up by [`defer`].
+    fn run_deferred(&mut self, ctx: &mut EventCtx) {
+        if let Some(rect) = self.scroll_request.take() {
+            ctx.scroll_area_to_view(rect);
+        }
+        // the offset is restored once the visible part of the grid is known
+        if self.viewport.area() > 0. {
+            if let Some(offset) = self.offset_request.take() {
+                self.scroll_to_offset(ctx, offset);
+            }
+        }
+        if self.pending_bc.is_some() {
+            self.incremental_pass = true;
+            ctx.request_layout();
+        }
+        if let Some(target) = self.metrics_target {
+            let metrics = self.metrics(ctx.size());
+            // an empty viewport means the grid wasn't placed in the window yet
+            let placed =
+                self.viewport.area() > 0. || metrics.content_size.area() == 0.;
+            if placed && self.metrics_sent != Some(metrics) {
+                self.metrics_sent = Some(metrics);
+                ctx.submit_command(CONTENT_METRICS.with(metrics).to(target));
+            }
         }
     }
 
-    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut T, usize)) {
-        for (i, item) in self.iter_mut().enumerate() {
-            cb(item, i);
+    /// Ask the enclosing `Scroll` to show the grid from `offset` on.
+    fn scroll_to_offset(&self, ctx: &mut EventCtx, offset: Vec2) {
+        let size = self.viewport.size();
+        ctx.scroll_area_to_view(Rect::from_origin_size(
+            offset.to_point(),
+            size,
+        ));
+    }
+
+    /// Track the part of the grid that is visible, given the part of its
+    /// surroundings that is visible, relative to the grid.
+    ///
+    /// Returns whether the grid has deferred work because of it.
+    fn set_visible(&mut self, visible: Rect, size: Size) -> bool {
+        self.visible = visible;
+        let viewport = visible.intersect(size.to_rect());
+        if viewport == self.viewport {
+            return false;
         }
+        self.viewport = viewport;
+        let offset = viewport.origin().to_vec2();
+        self.offset_request.is_some()
+            || self.metrics_target.is_some()
+            || (self.scroll_offset_sink.is_some()
+                && offset != self.offset_written)
     }
 
-    fn data_len(&self) -> usize {
-        self.len()
+    /// What [`CONTENT_METRICS`] carries for a grid of `content_size`.
+    fn metrics(&self, content_size: Size) -> ContentMetrics {
+        ContentMetrics {
+            content_size,
+            viewport_size: self.viewport.size(),
+            scroll_offset: self.viewport.origin().to_vec2(),
+        }
     }
 
-    fn child_data(&self) -> Option<T> {
-        Some(self.iter().next().unwrap().clone())
+    /// Returns the index of the grid item under `pos`.
+    ///
+    /// Where items overlap, the one painted on top wins.
+    fn cell_at(&self, pos: Point) -> Option<usize> {
+        let order: Vec<usize> = self
+            .paint_order()
+            .into_iter()
+            .rev()
+            .filter(|idx| self.is_laid_out(*idx))
+            .collect();
+        let rects = order.iter().map(|idx| self.children[*idx].layout_rect());
+        hit_test(rects, pos, self.min_touch_target).map(|hit| order[hit])
     }
-}
 
-impl<C: Data, T: GridIter<C>> Widget<T> for GridView<C> {
-    fn event(
-        &mut self,
-        ctx: &mut druid::EventCtx,
-        event: &druid::Event,
-        data: &mut T,
-        env: &druid::Env,
-    ) {
-        let mut children = self.children.iter_mut();
-        data.for_each_mut(|child_data, _| {
-            if let Some(child) = children.next() {
-                child.event(ctx, event, child_data, env);
-            }
-        })
+    /// Returns the index of the grid item under `pos`, unless it is disabled.
+    fn enabled_cell_at(&self, pos: Point) -> Option<usize> {
+        self.cell_at(pos).filter(|idx| !self.is_disabled(*idx))
     }
 
-    fn lifecycle(
-        &mut self,
-        ctx: &mut druid::LifeCycleCtx,
-        event: &druid::LifeCycle,
-        data: &T,
-        env: &druid::Env,
-    ) {
-        if let LifeCycle::WidgetAdded = event {
-            if self.update_child_count(data, env) {
-                ctx.children_changed();
-            }
+    /// Whether the item at `idx` was disabled by [`GridIter::is_disabled`] as of
+    /// the last update.
+    fn is_disabled(&self, idx: usize) -> bool {
+        self.disabled.get(idx).copied().unwrap_or(false)
+    }
+
+    /// Remember which items are disabled so events don't need the data to check.
+    fn update_disabled(&mut self, data: &impl GridIter<T>) {
+        self.disabled = (0..data.data_len())
+            .map(|idx| data.is_disabled(idx))
+            .collect();
+    }
+
+    /// Set the item under the mouse.
+    ///
+    /// Returns the paint rects of the items that were entered or left when they
+    /// need to be repainted.
+    fn set_hovered(&mut self, hovered: Option<usize>) -> Vec<Rect> {
+        let old = std::mem::replace(&mut self.hovered, hovered);
+        let repaint = self.repaint_on_hover || self.cell_state_key.is_some();
+        if old == hovered || !repaint {
+            return Vec::new();
         }
+        [old, hovered]
+            .iter()
+            .flatten()
+            .filter_map(|idx| self.children.get(*idx))
+            .map(|child| child.paint_rect())
+            .collect()
+    }
 
-        let mut children = self.children.iter_mut();
-        data.for_each(|child_data, _| {
-            if let Some(child) = children.next() {
-                child.lifecycle(ctx, event, child_data, env);
-            }
-        });
+    /// The state of each item that isn't in the default state, by index, or
+    /// nothing if [`with_cell_state_env`] isn't used.
+    fn cell_states(&self) -> HashMap<usize, CellState> {
+        let mut states: HashMap<usize, CellState> = HashMap::new();
+        if self.cell_state_key.is_none() {
+            return states;
+        }
+        for idx in &self.selection {
+            states.entry(*idx).or_default().selected = true;
+        }
+        if let Some(idx) = self.focused {
+            states.entry(idx).or_default().focused = true;
+        }
+        if let Some(idx) = self.hovered {
+            states.entry(idx).or_default().hovered = true;
+        }
+        for (idx, _) in self.disabled.iter().enumerate().filter(|(_, d)| **d) {
+            states.entry(idx).or_default().disabled = true;
+        }
+        let len = self.children.len();
+        states.retain(|idx, _| *idx < len);
+        states
     }
 
-    fn update(
-        &mut self,
-        ctx: &mut druid::UpdateCtx,
-        _old_data: &T,
-        data: &T,
-        env: &druid::Env,
-    ) {
-        // we send update to children first, before adding or removing children;
-        // this way we avoid sending update to newly added children, at the cost
-        // of potentially updating children that are going to be removed.
-        let mut children = self.children.iter_mut();
-        data.for_each(|child_data, _| {
-            if let Some(child) = children.next() {
-                child.update(ctx, child_data, env);
+    /// The env for each item with its [`CellState`] added, or just `env` if
+    /// [`with_cell_state_env`] isn't used.
+    ///
+    /// The envs are only made again when `env` or the state of an item changed
+    /// since the last call.
+    fn cell_envs(&mut self, env: &Env) -> Rc<CellEnvs> {
+        let states = self.cell_states();
+        let cached = &self.cell_envs;
+        if cached.states == states
+            && cached.base.as_ref().is_some_and(|base| base.same(env))
+        {
+            return cached.clone();
+        }
+        let (default, envs) = match &self.cell_state_key {
+            Some(key) => {
+                let envs = states
+                    .iter()
+                    .map(|(idx, state)| {
+                        let bits = state.to_bits();
+                        (*idx, env.clone().adding(key.clone(), bits))
+                    })
+                    .collect();
+                let default = CellState::default().to_bits();
+                (Some(env.clone().adding(key.clone(), default)), envs)
             }
+            None => (None, HashMap::new()),
+        };
+        self.cell_envs = Rc::new(CellEnvs {
+            base: Some(env.clone()),
+            states,
+            default,
+            envs,
         });
+        self.cell_envs.clone()
+    }
 
-        if self.update_child_count(data, env) {
-            ctx.children_changed();
+    /// Returns how many items would be laid out on the minor axis before wrapping
+    /// if the grid had `available_minor` space on that axis, without doing a layout.
+    ///
+    /// In [`wrap`] mode this uses the item size measured during the last layout.
+    pub fn preferred_columns(&self, available_minor: f64, env: &Env) -> usize {
+        let (_, minor_spacing) = self.spacing(env);
+        // the margins at the edges of the grid are never collapsed
+        let available_minor =
+            available_minor - 2. * self.cell_margin.resolve(env);
+        let tracks = self.column_weights.as_ref().and_then(|weights| {
+            track_sizes(weights, available_minor, minor_spacing)
+        });
+        if let Some(tracks) = tracks {
+            return tracks.len();
+        }
+        match self.minor_axis_count {
+            MinorAxisCount::Wrap => {
+                if self.cell_size == Size::ZERO {
+                    // TODO: this should be zero, but i'm making it one to avoid divide by zero
+                    1
+                } else {
+                    let cell_minor = self.axis.minor(self.cell_size);
+                    let count = (available_minor / cell_minor).floor();
+                    if self.min_wrap_gap <= 0. {
+                        return count as usize;
+                    }
+                    let used =
+                        count * cell_minor + (count - 1.) * minor_spacing;
+                    if count > 1. && available_minor - used < self.min_wrap_gap
+                    {
+                        count as usize - 1
+                    } else {
+                        count as usize
+                    }
+                }
+            }
+            MinorAxisCount::Count(count) => count as usize,
         }
     }
 
-    fn layout(
-        &mut self,
-        ctx: &mut druid::LayoutCtx,
-        bc: &druid::BoxConstraints,
-        data: &T,
-        env: &druid::Env,
-    ) -> druid::Size {
-        let axis = self.axis;
-        let (major_spacing, minor_spacing) = match axis {
+    /// Returns the space between neighboring items on the major and minor axis,
+    /// including their margins.
+    fn spacing(&self, env: &Env) -> (f64, f64) {
+        let margin = self.cell_margin.resolve(env);
+        let margin_gap = if self.collapse_margins {
+            margin
+        } else {
+            2. * margin
+        };
+        let (major_spacing, minor_spacing) = match self.axis {
             Axis::Vertical => (
                 self.vertical_spacing.resolve(env),
                 self.horizontal_spacing.resolve(env),
@@ -345,63 +1399,864 @@ impl<C: Data, T: GridIter<C>> Widget<T> for GridView<C> {
                 self.vertical_spacing.resolve(env),
             ),
         };
-        let mut major_pos = 0.0;
-        let mut minor_pos = 0.0;
-        let mut paint_rect = Rect::ZERO;
-        // let child_bc = constraints(axis, bc, 0., f64::INFINITY);
-        // I don't know if this is the right way to go. I would assume a grid is
-        // used in a Scroll and that would provide the infinite constraints if necessary
-        // otherwise the scroll will be locked to an axis and provide concrete constraints
-        // on that axis
-        // this has to use axis.constraints function but it is private
-        // reimplemented below for convenience
-        let child_bc = constraints(axis, bc, 0., axis.major(bc.max()));
+        (major_spacing + margin_gap, minor_spacing + margin_gap)
+    }
 
-        // let child_bc = constraints(axis, bc, 0., );
+    /// Whether the grid takes keyboard focus to track a focused item.
+    fn wants_focus(&self) -> bool {
+        self.on_cell_key.is_some()
+            || self.on_item_click.is_some()
+            || self.ripple_color.is_some()
+            || self.selection_follows_focus
+    }
 
-        let minor_axis_count = match self.minor_axis_count {
-            MinorAxisCount::Wrap => {
-                let minor_len = axis.minor(bc.max());
-                let child_size = match self.children.last_mut() {
-                    Some(child) => {
-                        let size = child.layout(
-                            ctx,
-                            &child_bc,
-                            &data.child_data().unwrap(),
-                            env,
-                        );
-                        size
+    /// The item focus moves to from the focused item when `key` is pressed, if
+    /// it is an arrow key.
+    ///
+    /// Keys along the minor axis move through the items in data order, keys along
+    /// the major axis move to the closest item in the next row/column. Disabled
+    /// items are skipped.
+    fn focus_neighbor(&self, key: &KbKey) -> Option<usize> {
+        let (along_major, forward) = match (self.axis, key) {
+            (Axis::Vertical, KbKey::ArrowDown)
+            | (Axis::Horizontal, KbKey::ArrowRight) => (true, true),
+            (Axis::Vertical, KbKey::ArrowUp)
+            | (Axis::Horizontal, KbKey::ArrowLeft) => (true, false),
+            (Axis::Vertical, KbKey::ArrowRight)
+            | (Axis::Horizontal, KbKey::ArrowDown) => (false, true),
+            (Axis::Vertical, KbKey::ArrowLeft)
+            | (Axis::Horizontal, KbKey::ArrowUp) => (false, false),
+            _ => return None,
+        };
+        let len = self.children.len();
+        let enabled =
+            |idx: &usize| !self.is_disabled(*idx) && self.is_laid_out(*idx);
+        let current = match self.focused.filter(|idx| *idx < len) {
+            Some(current) => current,
+            None => return (0..len).find(enabled),
+        };
+        if !along_major {
+            return if forward {
+                (current + 1..len).find(enabled)
+            } else {
+                (0..current).rev().find(enabled)
+            };
+        }
+
+        let axis = self.axis;
+        let rect = self.children[current].layout_rect();
+        let start = axis.major_pos(rect.origin());
+        let end = axis.major_pos(Point::new(rect.x1, rect.y1));
+        let center = axis.minor_pos(rect.center());
+        // how far an item is past the focused one on the major axis, and how far
+        // off to the side it is
+        let distance = |idx: usize| {
+            let other = self.children[idx].layout_rect();
+            let gap = if forward {
+                axis.major_pos(other.origin()) - end
+            } else {
+                start - axis.major_pos(Point::new(other.x1, other.y1))
+            };
+            (gap, (axis.minor_pos(other.center()) - center).abs())
+        };
+        (0..len)
+            .filter(enabled)
+            .filter(|idx| distance(*idx).0 >= 0.)
+            .min_by(|a, b| {
+                distance(*a)
+                    .partial_cmp(&distance(*b))
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+
+    /// Move focus between items with the arrow keys.
+    fn navigate_event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        let key = match event {
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_handled() => key,
+            _ => return,
+        };
+        let next = match self.focus_neighbor(&key.key) {
+            Some(next) => next,
+            None => return,
+        };
+        if let Some(selection) = self.focus(next) {
+            self.set_selection(ctx, selection);
+        }
+        ctx.scroll_area_to_view(self.children[next].layout_rect());
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    /// Move focus to the item at `idx`.
+    ///
+    /// Returns the selection to switch to if the selection follows focus.
+    fn focus(&mut self, idx: usize) -> Option<Vec<usize>> {
+        self.focused = Some(idx);
+        self.selection_follows_focus.then(|| vec![idx])
+    }
+
+    /// Call the click callback for items clicked with the mouse, or activated
+    /// from the keyboard while focused.
+    fn click_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut impl GridIter<T>,
+        env: &Env,
+    ) {
+        let clicked = match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if !ctx.is_handled() {
+                    self.pressed = self.enabled_cell_at(mouse.pos);
+                    if let Some(idx) = self.pressed {
+                        self.start_ripple(ctx, idx, mouse.pos, env);
                     }
-                    None => Size::ZERO,
-                };
-                if child_size == Size::ZERO {
-                    // TODO: this should be zero, but i'm making it one to avoid divide by zero
-                    1
-                } else {
-                    (minor_len / axis.minor(child_size)).floor() as usize
                 }
+                None
             }
-            MinorAxisCount::Count(count) => count as usize,
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                let dragged =
+                    matches!(self.drag, Some(Drag { active: true, .. }));
+                let pressed = self.pressed.take().filter(|_| !dragged);
+                pressed
+                    .filter(|idx| self.enabled_cell_at(mouse.pos) == Some(*idx))
+            }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_handled() => {
+                let activates = is_activation_key(&key.key);
+                let activated = self.focused.filter(|_| activates);
+                if let Some(idx) = activated {
+                    let cell = self.children.get(idx);
+                    let center = cell.map_or(Point::ORIGIN, |cell| {
+                        cell.layout_rect().center()
+                    });
+                    self.start_ripple(ctx, idx, center, env);
+                }
+                activated
+            }
+            _ => None,
         };
+        if let (Some(clicked), Some(cb)) = (clicked, &self.on_item_click) {
+            data.for_each_mut(|item, idx| {
+                if idx == clicked {
+                    cb(ctx, item, idx);
+                }
+            });
+            ctx.set_handled();
+        }
+    }
 
-        let mut children = self.children.iter_mut();
+    /// Start a ripple over the item at `idx`, replacing any ripple that is still
+    /// spreading.
+    fn start_ripple(
+        &mut self,
+        ctx: &mut EventCtx,
+        idx: usize,
+        center: Point,
+        env: &Env,
+    ) {
+        if self.ripple_color.is_none() || self.reduces_motion(env) {
+            return;
+        }
+        self.ripple = Some(Ripple { idx, center, t: 0. });
+        ctx.request_anim_frame();
+    }
 
-        // data.row(
-        //     |child_data, idx| {
-        //         let child = match children.next() {
-        //             Some(child) => child,
-        //             None => return,
-        //         };
+    /// Restart the tooltip delay whenever the mouse moves over an item, and hide
+    /// the tooltip once the mouse leaves the item it belongs to.
+    fn tooltip_mouse_move(
+        &mut self,
+        ctx: &mut EventCtx,
+        hovered: Option<usize>,
+        window_pos: Point,
+    ) {
+        let delay = match self.tooltip_delay {
+            Some(delay) => delay,
+            None => return,
+        };
+        let (hide, wait_for) = self.tooltip_hover(hovered);
+        if hide {
+            ctx.submit_command(HIDE_TOOLTIP);
+        }
+        self.tooltip_timer =
+            wait_for.map(|idx| (ctx.request_timer(delay), idx, window_pos));
+    }
 
-        //         let child_size = child.layout(ctx, &child_bc, child_data, env);
-        //         let child_pos: Point = axis.pack(major_pos, minor_pos).into();
-        //         child.set_origin(ctx, child_data, env, child_pos);
-        //         paint_rect = paint_rect.union(child.paint_rect());
+    /// Forget the shown tooltip if the mouse left its item.
+    ///
+    /// Returns whether the tooltip has to be hidden, and the item whose tooltip
+    /// delay starts over, if any.
+    fn tooltip_hover(
+        &mut self,
+        hovered: Option<usize>,
+    ) -> (bool, Option<usize>) {
+        let hide =
+            self.tooltip_shown.is_some() && self.tooltip_shown != hovered;
+        if hide {
+            self.tooltip_shown = None;
+        }
+        let wait_for = hovered.filter(|idx| self.tooltip_shown != Some(*idx));
+        (hide, wait_for)
+    }
 
-        //         if (idx + 1) % minor_axis_count == 0 {
-        //             // TODO: have to correct overshoot
-        //             major_pos += axis.major(child_size) + major_spacing;
-        //             minor_pos = 0.;
+    /// Replace the selected items, notifying the selection callback if anything
+    /// changed.
+    fn set_selection(&mut self, ctx: &mut EventCtx, selection: Vec<usize>) {
+        if self.selection == selection {
+            return;
+        }
+        self.selection = selection;
+        if self.selection.is_empty() {
+            self.selection_mode = false;
+        }
+        if let Some(cb) = &self.on_selection_change {
+            cb(ctx, &self.selection);
+        }
+        ctx.request_paint();
+    }
+
+    /// The index of the column border at `pos`, if any. Border `i` is the one
+    /// after column `i`.
+    fn column_border_at(&self, pos: Point, env: &Env) -> Option<usize> {
+        let (_, minor_spacing) = self.spacing(env);
+        let pos = self.axis.minor_pos(pos);
+        let mut border = self.cell_margin.resolve(env) - minor_spacing / 2.;
+        let inner = self.tracks.len().saturating_sub(1);
+        self.tracks[..inner].iter().position(|track| {
+            border += track + minor_spacing;
+            (pos - border).abs() <= RESIZE_GRIP
+        })
+    }
+
+    /// Resize the two columns next to a border dragged by the user.
+    fn resize_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        let cursor = match self.axis {
+            Axis::Vertical => druid::Cursor::ResizeLeftRight,
+            Axis::Horizontal => druid::Cursor::ResizeUpDown,
+        };
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if ctx.is_handled() {
+                    return;
+                }
+                if let Some(border) = self.column_border_at(mouse.pos, env) {
+                    let start = self.axis.minor_pos(mouse.pos);
+                    self.resizing = Some((border, start));
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseMove(mouse) => match self.resizing {
+                Some((border, last)) => {
+                    let pos = self.axis.minor_pos(mouse.pos);
+                    self.move_border(border, last, pos);
+                    ctx.set_cursor(&cursor);
+                    ctx.request_layout();
+                    ctx.set_handled();
+                }
+                None => {
+                    if self.column_border_at(mouse.pos, env).is_some() {
+                        ctx.set_cursor(&cursor);
+                    }
+                }
+            },
+            Event::MouseUp(mouse)
+                if mouse.button.is_left() && self.resizing.is_some() =>
+            {
+                self.resizing = None;
+                ctx.set_active(false);
+                if let Some(cb) = &self.on_column_resize {
+                    cb(ctx, &self.tracks);
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    /// Move column border `border`, last dragged to `last`, towards `pos` on the
+    /// minor axis by resizing the columns on either side of it.
+    fn move_border(&mut self, border: usize, last: f64, pos: f64) {
+        // neither column can shrink below nothing
+        let (before, after) = (self.tracks[border], self.tracks[border + 1]);
+        let delta = (pos - last).clamp(-before, after);
+        self.tracks[border] = before + delta;
+        self.tracks[border + 1] = after - delta;
+        self.column_weights = Some(self.tracks.clone());
+        self.resizing = Some((border, last + delta));
+    }
+
+    /// Add the clicked item to the ordered selection or remove it from it.
+    fn pick_event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        let mouse = match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => mouse,
+            _ => return,
+        };
+        if ctx.is_handled() {
+            return;
+        }
+        if let Some(idx) = self.enabled_cell_at(mouse.pos) {
+            self.toggle_selected(ctx, idx);
+            ctx.set_handled();
+        }
+    }
+
+    /// Add the item at `idx` to the end of the selection, or remove it if it is
+    /// already selected.
+    fn toggle_selected(&mut self, ctx: &mut EventCtx, idx: usize) {
+        let selection = self.toggled(idx);
+        self.set_selection(ctx, selection);
+    }
+
+    /// The selection with the item at `idx` added, or removed if it was selected.
+    fn toggled(&self, idx: usize) -> Vec<usize> {
+        let mut selection = self.selection.clone();
+        match selection.iter().position(|picked| *picked == idx) {
+            Some(pos) => {
+                selection.remove(pos);
+            }
+            None => selection.push(idx),
+        }
+        selection
+    }
+
+    /// Enter selection mode after the item at `idx` was held down long enough.
+    ///
+    /// Returns the selection to switch to if the item wasn't selected yet.
+    fn long_pressed(&mut self, idx: usize) -> Option<Vec<usize>> {
+        self.long_press_timer = None;
+        // the press became a long-press, so releasing it is no longer a click
+        // and moving no longer a drag
+        self.pressed = None;
+        self.drag = None;
+        self.selection_mode = true;
+        (!self.selection.contains(&idx)).then(|| self.toggled(idx))
+    }
+
+    /// Wait for a press to turn into a long-press, and toggle pressed items while
+    /// in selection mode.
+    fn long_press_event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        let delay = match self.long_press {
+            Some(delay) => delay,
+            None => return,
+        };
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if ctx.is_handled() {
+                    return;
+                }
+                let pressed = self.enabled_cell_at(mouse.pos);
+                match pressed {
+                    Some(idx) if self.selection_mode => {
+                        self.toggle_selected(ctx, idx);
+                        ctx.set_handled();
+                    }
+                    Some(idx) => {
+                        let token = ctx.request_timer(delay);
+                        self.long_press_timer = Some((token, idx, mouse.pos));
+                    }
+                    None => (),
+                }
+            }
+            Event::MouseMove(mouse) => {
+                // moving turns the press into a drag or a scroll instead
+                if let Some((_, _, start)) = self.long_press_timer {
+                    if (mouse.pos - start).hypot() > DRAG_THRESHOLD {
+                        self.long_press_timer = None;
+                    }
+                }
+            }
+            Event::MouseUp(_) => self.long_press_timer = None,
+            _ => (),
+        }
+    }
+
+    /// The picked items stored in `data`, if [`with_ordered_selection`] is
+    /// enabled.
+    fn read_selection(&self, data: &D) -> Option<OrdSet<usize>> {
+        let lens = self.ordered_selection.as_ref()?;
+        Some((lens.get)(data))
+    }
+
+    /// Store the selection in `data` if it is enabled and has changed.
+    fn write_selection(&self, data: &mut D) {
+        if let Some(lens) = &self.ordered_selection {
+            let picked: OrdSet<usize> =
+                self.selection.iter().copied().collect();
+            if (lens.get)(data) != picked {
+                (lens.put)(data, picked);
+            }
+        }
+    }
+
+    /// Remember the column count found by a layout, so it is written to the data
+    /// if it changed.
+    fn set_column_count(&mut self, count: usize) {
+        if count != self.column_count {
+            self.column_count = count;
+            self.column_count_written = false;
+        }
+    }
+
+    /// Store the column count in `data` if there is a sink for it and the count
+    /// hasn't been written since it last changed.
+    fn write_column_count(&mut self, data: &mut D) {
+        if let Some(sink) = &self.column_count_sink {
+            if !self.column_count_written {
+                sink(data, self.column_count);
+                self.column_count_written = true;
+            }
+        }
+    }
+
+    /// Store the scroll offset in `data` if there is a sink for it and the offset
+    /// changed since it was last written.
+    fn write_scroll_offset(&mut self, data: &mut D) {
+        let offset = self.viewport.origin().to_vec2();
+        if let Some(sink) = &self.scroll_offset_sink {
+            if offset != self.offset_written {
+                sink(data, offset);
+                self.offset_written = offset;
+            }
+        }
+    }
+
+    /// Select the items covered by a rectangle dragged over the grid.
+    fn marquee_event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        // mouse positions are already local to the grid, so they account for
+        // any scrolling; clamping keeps the marquee inside the grid
+        let bounds = ctx.size().to_rect();
+        let clamp = |pos: Point| clamp_point(pos, bounds);
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if ctx.is_handled() || self.drag.is_some() {
+                    return;
+                }
+                let start = clamp(mouse.pos);
+                self.marquee = Some((start, start));
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) => {
+                let start = match self.marquee {
+                    Some((start, _)) => start,
+                    None => return,
+                };
+                let end = clamp(mouse.pos);
+                self.marquee = Some((start, end));
+                let marquee = Rect::from_points(start, end);
+                let rects =
+                    self.children.iter().map(|child| child.layout_rect());
+                let selection = marquee_hits(marquee, rects)
+                    .filter(|idx| !self.is_disabled(*idx))
+                    .collect();
+                self.set_selection(ctx, selection);
+                ctx.request_paint();
+            }
+            Event::MouseUp(mouse)
+                if mouse.button.is_left() && self.marquee.is_some() =>
+            {
+                self.marquee = None;
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether grid items can be dragged.
+    fn can_drag(&self) -> bool {
+        self.drop_target_highlight.is_some()
+            || self.on_reorder_complete.is_some()
+            || self.drag_lift.is_some()
+    }
+
+    /// How far each item is painted from its place while an item is lifted by a
+    /// drag, either following the mouse or making room for the lifted item.
+    ///
+    /// Empty when no item is lifted.
+    fn lift_offsets(&self) -> Vec<Vec2> {
+        let len = self.children.len();
+        let drag = match &self.drag {
+            Some(drag) if drag.active && self.drag_lift.is_some() => drag,
+            _ => return Vec::new(),
+        };
+        // the items a cancelled drag was about may be gone
+        if drag.cancelled || drag.source >= len {
+            return Vec::new();
+        }
+        let origin = |idx: usize| self.children[idx].layout_rect().origin();
+        let source = drag.source;
+        let target = drag.target.filter(|target| *target < len);
+        // the items between the lifted item and where it would be dropped move
+        // one place towards the lifted item, leaving a gap at the drop position
+        (0..len)
+            .map(|idx| match target {
+                _ if idx == source => drag.pos - drag.start,
+                Some(target) if source < idx && idx <= target => {
+                    origin(idx - 1) - origin(idx)
+                }
+                Some(target) if target <= idx && idx < source => {
+                    origin(idx + 1) - origin(idx)
+                }
+                _ => Vec2::ZERO,
+            })
+            .collect()
+    }
+
+    /// How the item at `idx` is moved from where it is laid out when it is
+    /// painted, given the [`lift_offsets`] of the items.
+    fn paint_transform(&self, idx: usize, offsets: &[Vec2]) -> Affine {
+        let offset = offsets.get(idx).copied().unwrap_or(Vec2::ZERO);
+        let progress = self.appear.get(idx).map_or(1., |t| t / APPEAR_DURATION);
+        let entrance = self.entrance.unwrap_or(Entrance::Fade);
+        let rect = self.children[idx].layout_rect();
+        Affine::translate(offset) * entrance.transform(rect, progress)
+    }
+
+    /// Move a [`SCROLL_TO_VIEW`] request from a widget inside an item, or the
+    /// aggregate row, to where that is painted before it goes on to the
+    /// enclosing `Scroll`.
+    fn scroll_notification(&self, ctx: &mut EventCtx, note: &Notification) {
+        let rect = match note.get(SCROLL_TO_VIEW) {
+            Some(rect) => *rect,
+            None => return,
+        };
+        let row = self.aggregate_row.as_ref();
+        let transform = if row.map(|row| row.id()) == Some(note.route()) {
+            Affine::translate(self.aggregate_shift)
+        } else {
+            let sender =
+                self.children.iter().position(|c| c.id() == note.route());
+            match sender {
+                Some(idx) => self.paint_transform(idx, &self.lift_offsets()),
+                None => return,
+            }
+        };
+        if transform == Affine::IDENTITY {
+            return;
+        }
+        // the request is in window coordinates, the transform in the grid's
+        let origin = ctx.window_origin().to_vec2();
+        let painted = transform.transform_rect_bbox(rect - origin) + origin;
+        ctx.set_handled();
+        ctx.submit_notification(SCROLL_TO_VIEW.with(painted));
+    }
+
+    /// The item under the mouse during a drag, unless it is the dragged item,
+    /// since dropping an item back onto itself does nothing.
+    fn drop_target(&self) -> Option<usize> {
+        let drag = self.drag.as_ref().filter(|drag| drag.active)?;
+        drag.target.filter(|target| *target != drag.source)
+    }
+
+    /// Track a drag from one grid item to another, moving the item when it is
+    /// dropped.
+    fn drag_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut impl GridIter<T>,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if ctx.is_handled() {
+                    return;
+                }
+                if let Some(source) = self.enabled_cell_at(mouse.pos) {
+                    self.drag = Some(Drag {
+                        source,
+                        start: mouse.pos,
+                        pos: mouse.pos,
+                        active: false,
+                        target: None,
+                        cancelled: false,
+                    });
+                }
+            }
+            Event::MouseMove(mouse) => {
+                let hit = self.cell_at(mouse.pos);
+                if let Some(drag) = &mut self.drag {
+                    if !drag.active
+                        && mouse.buttons.has_left()
+                        && (mouse.pos - drag.start).hypot() > DRAG_THRESHOLD
+                    {
+                        drag.active = true;
+                        ctx.set_active(true);
+                    }
+                    if drag.active && !drag.cancelled && drag.target != hit {
+                        drag.target = hit;
+                        ctx.request_paint();
+                    }
+                    drag.pos = mouse.pos;
+                    if drag.active && self.drag_lift.is_some() {
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                let drag = match self.drag.take() {
+                    Some(drag) if drag.active => drag,
+                    _ => return,
+                };
+                ctx.set_active(false);
+                ctx.request_paint();
+                if let Some(target) =
+                    drag.target.filter(|target| *target != drag.source)
+                {
+                    if !data.move_item(drag.source, target) {
+                        return;
+                    }
+                    self.item_moved(drag.source, target);
+                    let selection = self
+                        .selection
+                        .iter()
+                        .map(|idx| moved_index(*idx, drag.source, target))
+                        .collect();
+                    self.set_selection(ctx, selection);
+                    if let Some(cb) = &self.on_reorder_complete {
+                        cb(ctx, drag.source, target);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Keep the widget and state of an item with it after it moved in the data
+    /// from `from` to `to`. The selection is left to the caller, which has to
+    /// notify about the change.
+    fn item_moved(&mut self, from: usize, to: usize) {
+        move_entry(&mut self.children, from, to);
+        move_entry(&mut self.kinds, from, to);
+        move_entry(&mut self.disabled, from, to);
+        move_entry(&mut self.appear, from, to);
+        let remap = |idx: usize| moved_index(idx, from, to);
+        self.focused = self.focused.map(remap);
+        self.hovered = self.hovered.map(remap);
+        self.pressed = self.pressed.map(remap);
+        self.tooltip_shown = self.tooltip_shown.map(remap);
+        if let Some(ripple) = &mut self.ripple {
+            ripple.idx = remap(ripple.idx);
+        }
+    }
+
+    /// Queue the scroll to the initial item, once that item exists.
+    fn request_initial_scroll(&mut self) {
+        let initial_child = self
+            .initial_scroll
+            .filter(|idx| self.is_laid_out(*idx))
+            .and_then(|idx| self.children.get(idx));
+        if let Some(child) = initial_child {
+            self.scroll_request = Some(child.layout_rect());
+            self.initial_scroll = None;
+        }
+    }
+
+    /// When the widget is created or the data changes, create or remove children as needed
+    ///
+    /// Returns `true` if children were added, removed or rebuilt.
+    fn update_child_count(
+        &mut self,
+        data: &impl GridIter<T>,
+        env: &Env,
+    ) -> bool {
+        let len = self.children.len();
+        let new_len = data.data_len();
+        self.laid_out = self.laid_out.min(new_len);
+        self.update_entrance(new_len, env);
+
+        // items whose kind changed get a widget of the new kind
+        let mut rebuilt = false;
+        if !self.builders.is_empty() {
+            for idx in 0..len.min(new_len) {
+                let kind = data.kind(idx);
+                if self.kinds[idx] != kind {
+                    let child = self.build(kind);
+                    let old = std::mem::replace(&mut self.children[idx], child);
+                    self.recycle(self.kinds[idx], old);
+                    self.kinds[idx] = kind;
+                    rebuilt = true;
+                }
+            }
+        }
+
+        match len.cmp(&new_len) {
+            Ordering::Greater => {
+                let removed = self.children.drain(new_len..);
+                let kinds = self.kinds.drain(new_len..);
+                let room = self.cache_capacity - self.cache.len();
+                self.cache.extend(kinds.zip(removed).take(room));
+            }
+            Ordering::Less => {
+                for idx in len..new_len {
+                    let kind = data.kind(idx);
+                    let child = self.build(kind);
+                    self.children.push(child);
+                    self.kinds.push(kind);
+                }
+            }
+            Ordering::Equal => (),
+        }
+        len != new_len || rebuilt
+    }
+
+    /// A widget for an item of `kind`, reused from the cache if there is one.
+    fn build(&mut self, kind: u32) -> ChildPod<T> {
+        let cached = self.cache.iter().rposition(|(cached, _)| *cached == kind);
+        if let Some(pos) = cached {
+            return self.cache.remove(pos).1;
+        }
+        let widget = match self.builders.get(&kind) {
+            Some(build) => build(),
+            None => (self.closure)(),
+        };
+        WidgetPod::new(widget)
+    }
+
+    /// Keep the widget of a removed item of `kind` if the cache has room.
+    fn recycle(&mut self, kind: u32, child: ChildPod<T>) {
+        if self.cache.len() < self.cache_capacity {
+            self.cache.push((kind, child));
+        }
+    }
+
+    /// Start the staggered entrance of the items the grid is about to get when
+    /// it grows to `len` items.
+    ///
+    /// Each item has the time since its entrance started, which is negative while
+    /// it waits for its turn.
+    fn update_entrance(&mut self, len: usize, env: &Env) {
+        let delay = match (self.stagger, self.entrance) {
+            _ if self.reduces_motion(env) => None,
+            (Some(delay), _) => Some(delay.as_secs_f64()),
+            (None, Some(_)) => Some(0.),
+            (None, None) => None,
+        };
+        let delay = match delay {
+            Some(delay) => delay,
+            None => {
+                self.appear.clear();
+                return;
+            }
+        };
+        let old_len = self.children.len();
+        self.appear.resize(old_len, APPEAR_DURATION);
+        self.appear.truncate(len);
+        for new in 0..len.saturating_sub(old_len) {
+            self.appear.push(-(new as f64) * delay);
+        }
+    }
+
+    /// Whether any item is still waiting for or in the middle of its entrance.
+    fn is_appearing(&self) -> bool {
+        self.appear.iter().any(|t| *t < APPEAR_DURATION)
+    }
+
+    /// Whether the item at `idx` has been placed by a layout. With
+    /// [`with_incremental_layout`] the items still waiting for their turn
+    /// haven't, so they can't be clicked, focused or scrolled to.
+    fn is_laid_out(&self, idx: usize) -> bool {
+        self.layout_budget.is_none() || idx < self.laid_out
+    }
+
+    /// The index of the first item the next layout pass doesn't lay out yet.
+    fn layout_end(&self) -> usize {
+        self.layout_budget
+            .map_or(usize::MAX, |budget| self.laid_out.saturating_add(budget))
+    }
+
+    /// Lay the items out in rows/columns.
+    fn layout_grid(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &D,
+        env: &Env,
+    ) -> Size
+    where
+        D: GridIter<T>,
+    {
+        let axis = self.axis;
+        let (major_spacing, minor_spacing) = self.spacing(env);
+        let margin = self.cell_margin.resolve(env);
+        let mut paint_rect = Rect::ZERO;
+        // let child_bc = constraints(axis, bc, 0., f64::INFINITY);
+        // I don't know if this is the right way to go. I would assume a grid is
+        // used in a Scroll and that would provide the infinite constraints if necessary
+        // otherwise the scroll will be locked to an axis and provide concrete constraints
+        // on that axis
+        // this has to use axis.constraints function but it is private
+        // reimplemented below for convenience
+        let child_bc = constraints(axis, bc, 0., axis.major(bc.max()));
+
+        // let child_bc = constraints(axis, bc, 0., );
+
+        // in flow mode items keep their own size on the minor axis and only wrap
+        // when they run out of space
+        let flow = self.intrinsic_width;
+        let ratio_range = self.ratio_range;
+        let cell_clip = self.cell_clip;
+        let minor_limit = axis.minor(bc.max()) - margin;
+
+        let tracks = self.column_weights.as_ref().and_then(|weights| {
+            let minor_len = axis.minor(bc.max()) - 2. * margin;
+            track_sizes(weights, minor_len, minor_spacing)
+        });
+        let tracks = tracks.filter(|_| !flow);
+        self.tracks = tracks.clone().unwrap_or_default();
+
+        if !flow && matches!(self.minor_axis_count, MinorAxisCount::Wrap) {
+            self.cell_size = match self.children.last_mut() {
+                Some(child) => child.layout(
+                    ctx,
+                    &child_bc,
+                    &data.child_data().unwrap(),
+                    env,
+                ),
+                None => Size::ZERO,
+            };
+        }
+        let minor_axis_count = if flow {
+            usize::MAX
+        } else {
+            self.preferred_columns(axis.minor(bc.max()), env)
+        };
+
+        // with incremental layout only a budget of new items is laid out per
+        // pass, and the passes the grid asked for keep the items already placed
+        let laid_out = self.laid_out;
+        let layout_end = self.layout_end();
+        let resumed = self.incremental_pass && self.pending_bc == Some(*bc);
+        let anchored = if resumed { laid_out } else { 0 };
+
+        // headers span the whole minor axis
+        let header_bc =
+            constraints(axis, &bc.loosen(), 0., axis.major(bc.max()));
+        let mut sections = section_starts(data).into_iter().peekable();
+        let mut headers = self.headers.iter_mut();
+        let mut cursor = Cursor::new(margin, major_spacing);
+        // where each row/column starts and how long it is on the major axis and
+        // where it ends on the minor axis, and the row/column of each item
+        let mut lines: Vec<(f64, f64, f64)> = Vec::new();
+        let mut line_of = Vec::with_capacity(self.children.len());
+
+        let mut children = self.children.iter_mut();
+
+        // data.row(
+        //     |child_data, idx| {
+        //         let child = match children.next() {
+        //             Some(child) => child,
+        //             None => return,
+        //         };
+
+        //         let child_size = child.layout(ctx, &child_bc, child_data, env);
+        //         let child_pos: Point = axis.pack(major_pos, minor_pos).into();
+        //         child.set_origin(ctx, child_data, env, child_pos);
+        //         paint_rect = paint_rect.union(child.paint_rect());
+
+        //         if (idx + 1) % minor_axis_count == 0 {
+        //             // TODO: have to correct overshoot
+        //             major_pos += axis.major(child_size) + major_spacing;
+        //             minor_pos = 0.;
         //         } else {
         //             minor_pos += axis.minor(child_size) + minor_spacing;
         //         }
@@ -414,55 +2269,3556 @@ impl<C: Data, T: GridIter<C>> Widget<T> for GridView<C> {
                 Some(child) => child,
                 None => return,
             };
+            if idx >= layout_end {
+                let hidden = BoxConstraints::tight(Size::ZERO);
+                child.layout(ctx, &hidden, child_data, env);
+                child.set_origin(ctx, Point::ZERO);
+                return;
+            }
+
+            if sections.peek().map(|(start, _)| *start) == Some(idx) {
+                sections.next();
+                // a section always starts on a new row/column
+                if cursor.column != 0 {
+                    cursor.next_line();
+                }
+                if let Some((id, header)) = headers.next() {
+                    let header_size = header.layout(ctx, &header_bc, id, env);
+                    let header_pos: Point = axis.pack(cursor.major, 0.).into();
+                    header.set_origin(ctx, header_pos);
+                    paint_rect = paint_rect.union(header.paint_rect());
+                    cursor.major += axis.major(header_size) + major_spacing;
+                }
+            }
+
+            let track = tracks.as_ref().map(|tracks| tracks[cursor.column]);
+            let child_bc = match track {
+                Some(track) => BoxConstraints::new(
+                    Size::from(axis.pack(0., track)),
+                    Size::from(axis.pack(axis.major(child_bc.max()), track)),
+                ),
+                None => child_bc,
+            };
+            // anchored items that asked for a layout still get one
+            let child_size = if idx < anchored && !child.layout_requested() {
+                child.layout_rect().size()
+            } else {
+                let child_size = child.layout(ctx, &child_bc, child_data, env);
+                let ratio_bc = ratio_range.and_then(|range| {
+                    ratio_constraints(axis, range, child_size)
+                });
+                match ratio_bc {
+                    Some(ratio_bc) => {
+                        child.layout(ctx, &ratio_bc, child_data, env)
+                    }
+                    None => child_size,
+                }
+            };
+            if flow && !cursor.fits(axis.minor(child_size), minor_limit) {
+                cursor.next_line();
+            }
+            let child_pos: Point = axis.pack(cursor.major, cursor.minor).into();
+            child.set_origin(ctx, child_pos);
+            let rect = child.layout_rect();
+            paint_rect = paint_rect
+                .union(paint_bounds(cell_clip, rect, child.paint_rect()))
+                .union(rect.inflate(margin, margin));
 
-            let child_size = child.layout(ctx, &child_bc, child_data, env);
-            let child_pos: Point = axis.pack(major_pos, minor_pos).into();
-            child.set_origin(ctx, child_data, env, child_pos);
-            paint_rect = paint_rect.union(child.paint_rect());
+            let child_end = cursor.minor + axis.minor(child_size);
+            let child_major = axis.major(child_size);
+            match lines.last_mut() {
+                Some((major, major_len, end)) if *major == cursor.major => {
+                    *major_len = major_len.max(child_major);
+                    *end = end.max(child_end);
+                }
+                _ => lines.push((cursor.major, child_major, child_end)),
+            }
+            line_of.push(lines.len() - 1);
 
-            if (idx + 1) % minor_axis_count == 0 {
+            cursor.column += 1;
+            cursor.line_major = cursor.line_major.max(axis.major(child_size));
+            if cursor.column == minor_axis_count {
                 // have to correct overshoot
-                major_pos += axis.major(child_size) + major_spacing;
-                minor_pos = 0.;
+                cursor.next_line();
             } else {
-                minor_pos += axis.minor(child_size) + minor_spacing;
+                cursor.minor += track.unwrap_or_else(|| axis.minor(child_size))
+                    + minor_spacing;
             }
             // have to correct overshoot
         });
+        if cursor.column != 0 {
+            cursor
+                .row_pitch
+                .get_or_insert(cursor.line_major + major_spacing);
+        }
+        let len = self.children.len();
+        self.laid_out = layout_end.min(len);
+        self.pending_bc = Some(*bc).filter(|_| self.laid_out < len);
+        let trailing_edge = axis.minor(bc.max()) - margin;
+        if self.trailing_anchor && trailing_edge.is_finite() {
+            for (idx, child) in self.children.iter_mut().enumerate() {
+                let line = line_of.get(idx).map(|line| lines[*line]);
+                if let Some((_, _, line_end)) = line {
+                    let shift = trailing_shift(axis, trailing_edge, line_end);
+                    let origin = child.layout_rect().origin() + shift;
+                    child.set_origin(ctx, origin);
+                    paint_rect = paint_rect.union(paint_bounds(
+                        cell_clip,
+                        child.layout_rect(),
+                        child.paint_rect(),
+                    ));
+                }
+            }
+        }
+        self.longest_line = longest_line(&line_of);
+        self.rows = lines
+            .iter()
+            .map(|(major, major_len, _)| (*major, *major_len))
+            .collect();
+        if let Some(row) = &mut self.aggregate_row {
+            let row_major = if cursor.column != 0 {
+                cursor.major + cursor.line_major + major_spacing
+            } else {
+                cursor.major
+            };
+            row.layout(ctx, &header_bc, data, env);
+            row.set_origin(ctx, axis.pack(row_major, 0.).into());
+            paint_rect = paint_rect.union(row.paint_rect());
+        }
 
         // let my_size = bc.constrain(Size::from(axis.pack(major_pos, minor_pos)));
         // this should be correct, however the list widget uses above commented
         // code to get the widget size
-        let my_size = bc.constrain(paint_rect.size());
+        let content_size = paint_rect.size();
+        let snap_to_rows = self.snap_to_rows;
+        let my_size = grid_size(axis, bc, content_size, |major_len| {
+            if snap_to_rows {
+                cursor.snap(major_len)
+            } else {
+                major_len
+            }
+        });
         let insets = paint_rect - my_size.to_rect();
         ctx.set_paint_insets(insets);
+
         my_size
     }
 
-    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &T, env: &druid::Env) {
+    /// Lay the items out around a circle.
+    fn layout_radial(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &D,
+        env: &Env,
+        radius: f64,
+        start_angle: f64,
+    ) -> Size
+    where
+        D: GridIter<T>,
+    {
+        let child_bc = bc.loosen();
+        let mut sizes = Vec::with_capacity(self.children.len());
         let mut children = self.children.iter_mut();
         data.for_each(|child_data, _| {
             if let Some(child) = children.next() {
-                child.paint(ctx, child_data, env);
+                sizes.push(child.layout(ctx, &child_bc, child_data, env));
             }
         });
+
+        // leave room for the largest item wherever it lands on the circle
+        let largest = sizes.iter().fold(Size::ZERO, |largest, size| {
+            Size::new(
+                largest.width.max(size.width),
+                largest.height.max(size.height),
+            )
+        });
+        let center = Point::new(
+            radius + largest.width / 2.,
+            radius + largest.height / 2.,
+        );
+        let count = sizes.len();
+        let cell_clip = self.cell_clip;
+        let mut paint_rect = Rect::ZERO;
+        let children = self.children.iter_mut().zip(sizes);
+        for (idx, (child, size)) in children.enumerate() {
+            let child_center =
+                radial_center(center, radius, start_angle, idx, count);
+            let origin = Point::new(
+                child_center.x - size.width / 2.,
+                child_center.y - size.height / 2.,
+            );
+            child.set_origin(ctx, origin);
+            paint_rect = paint_rect.union(paint_bounds(
+                cell_clip,
+                child.layout_rect(),
+                child.paint_rect(),
+            ));
+        }
+        self.hide_headers(ctx, data, env);
+
+        let my_size = bc.constrain(Size::new(
+            2. * radius + largest.width,
+            2. * radius + largest.height,
+        ));
+        ctx.set_paint_insets(paint_rect - my_size.to_rect());
+        my_size
     }
-}
-/// Generate constraints with new values on the major axis.
-fn constraints(
-    axis: Axis,
-    bc: &BoxConstraints,
-    min_major: f64,
-    major: f64,
-) -> BoxConstraints {
-    match axis {
-        Axis::Horizontal => BoxConstraints::new(
-            Size::new(min_major, bc.min().height),
-            Size::new(major, bc.max().height),
-        ),
-        Axis::Vertical => BoxConstraints::new(
-            Size::new(bc.min().width, min_major),
-            Size::new(bc.max().width, major),
-        ),
+
+    /// Lay the items out wherever the function from [`with_layout_fn`] says.
+    fn layout_custom(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &D,
+        env: &Env,
+    ) -> Size
+    where
+        D: GridIter<T>,
+    {
+        let Arrangement::Custom(place) = &self.arrangement else {
+            unreachable!("only custom arrangements are laid out with a fn")
+        };
+        let child_bc = bc.loosen();
+        let count = self.children.len();
+        let cell_clip = self.cell_clip;
+        let mut paint_rect = Rect::ZERO;
+        let mut children = self.children.iter_mut();
+        data.for_each(|child_data, idx| {
+            if let Some(child) = children.next() {
+                let size = child.layout(ctx, &child_bc, child_data, env);
+                let origin = place(idx, count, size);
+                child.set_origin(ctx, origin);
+                paint_rect = paint_rect.union(paint_bounds(
+                    cell_clip,
+                    child.layout_rect(),
+                    child.paint_rect(),
+                ));
+            }
+        });
+        self.hide_headers(ctx, data, env);
+
+        let my_size = bc.constrain(paint_rect.size());
+        ctx.set_paint_insets(paint_rect - my_size.to_rect());
+        my_size
+    }
+
+    /// Lay the items out along the path from [`with_path_layout`].
+    fn layout_path(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &D,
+        env: &Env,
+    ) -> Size
+    where
+        D: GridIter<T>,
+    {
+        let Arrangement::Path(path) = &self.arrangement else {
+            unreachable!("only path arrangements are laid out along a path")
+        };
+        let segments: Vec<_> = path
+            .segments()
+            .map(|seg| (seg, seg.arclen(ARCLEN_ACCURACY)))
+            .collect();
+        let length: f64 = segments.iter().map(|(_, len)| len).sum();
+        let step = length / self.children.len().saturating_sub(1).max(1) as f64;
+
+        let child_bc = bc.loosen();
+        let cell_clip = self.cell_clip;
+        let mut paint_rect = Rect::ZERO;
+        let mut children = self.children.iter_mut();
+        data.for_each(|child_data, idx| {
+            if let Some(child) = children.next() {
+                let size = child.layout(ctx, &child_bc, child_data, env);
+                let center = point_along(&segments, step * idx as f64);
+                let origin = Point::new(
+                    center.x - size.width / 2.,
+                    center.y - size.height / 2.,
+                );
+                child.set_origin(ctx, origin);
+                paint_rect = paint_rect.union(paint_bounds(
+                    cell_clip,
+                    child.layout_rect(),
+                    child.paint_rect(),
+                ));
+            }
+        });
+        self.hide_headers(ctx, data, env);
+
+        let my_size = bc.constrain(paint_rect.size());
+        ctx.set_paint_insets(paint_rect - my_size.to_rect());
+        my_size
+    }
+
+    /// The bands `depth` deep along the edges of the visible area where the grid
+    /// continues past the edge, with the direction from the inside of each band
+    /// to its edge.
+    fn overflow_bands(
+        &self,
+        size: Size,
+        depth: f64,
+    ) -> Vec<(Rect, UnitPoint, UnitPoint)> {
+        let view = self.viewport;
+        if depth <= 0. || view.area() <= 0. {
+            return Vec::new();
+        }
+        let (start, end) = match self.axis {
+            Axis::Vertical => (
+                (view.y0 > 0.).then(|| {
+                    let band =
+                        Rect::new(view.x0, view.y0, view.x1, view.y0 + depth);
+                    (band, UnitPoint::BOTTOM, UnitPoint::TOP)
+                }),
+                (view.y1 < size.height).then(|| {
+                    let band =
+                        Rect::new(view.x0, view.y1 - depth, view.x1, view.y1);
+                    (band, UnitPoint::TOP, UnitPoint::BOTTOM)
+                }),
+            ),
+            Axis::Horizontal => (
+                (view.x0 > 0.).then(|| {
+                    let band =
+                        Rect::new(view.x0, view.y0, view.x0 + depth, view.y1);
+                    (band, UnitPoint::RIGHT, UnitPoint::LEFT)
+                }),
+                (view.x1 < size.width).then(|| {
+                    let band =
+                        Rect::new(view.x1 - depth, view.y0, view.x1, view.y1);
+                    (band, UnitPoint::LEFT, UnitPoint::RIGHT)
+                }),
+            ),
+        };
+        start.into_iter().chain(end).collect()
+    }
+
+    /// The stripes of the odd rows/columns that overlap `region`, spanning
+    /// `minor_len` on the minor axis.
+    fn stripe_rects(&self, minor_len: f64, region: Rect) -> Vec<Rect> {
+        let axis = self.axis;
+        // odd rows by their index in the whole grid, not the painted part
+        self.rows
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|(major, major_len)| {
+                Rect::from_origin_size(
+                    Point::from(axis.pack(*major, 0.)),
+                    Size::from(axis.pack(*major_len, minor_len)),
+                )
+            })
+            .filter(|stripe| stripe.intersect(region).area() > 0.)
+            .collect()
+    }
+
+    /// The indices of the items in the order they are painted, back to front.
+    fn paint_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.children.len()).collect();
+        if let Some(z_order) = &self.z_order {
+            // a stable sort keeps data order between items with the same z
+            order.sort_by_key(|idx| z_order(*idx));
+        }
+        order
+    }
+
+    /// Fade the items near the edges of the viewport into the background.
+    fn paint_edge_fade(&self, ctx: &mut druid::PaintCtx, env: &Env) {
+        // each band is a gradient that is opaque at the edge of the viewport
+        let background = self.fade_color.resolve(env);
+        let clear = background.with_alpha(0.);
+        for (band, from, to) in self.overflow_bands(ctx.size(), self.edge_fade)
+        {
+            let stops = (clear, background);
+            ctx.fill(band, &LinearGradient::new(from, to, stops));
+        }
+    }
+
+    /// Shade the edges of the visible area past which there are more items.
+    fn paint_scroll_shadows(&self, ctx: &mut druid::PaintCtx, env: &Env) {
+        let color = match &self.scroll_shadows {
+            Some(color) => color.resolve(env),
+            None => return,
+        };
+        let clear = color.with_alpha(0.);
+        let bands = self.overflow_bands(ctx.size(), SCROLL_SHADOW_DEPTH);
+        for (band, from, to) in bands {
+            let stops = (clear, color);
+            ctx.fill(band, &LinearGradient::new(from, to, stops));
+        }
+    }
+
+    /// Section headers and the aggregate row only show up in rows/columns, other
+    /// layouts give them no space.
+    fn hide_headers(&mut self, ctx: &mut LayoutCtx, data: &D, env: &Env)
+    where
+        D: GridIter<T>,
+    {
+        let hidden = BoxConstraints::tight(Size::ZERO);
+        for (id, header) in self.headers.iter_mut() {
+            header.layout(ctx, &hidden, id, env);
+            header.set_origin(ctx, Point::ZERO);
+        }
+        if let Some(row) = &mut self.aggregate_row {
+            row.layout(ctx, &hidden, data, env);
+            row.set_origin(ctx, Point::ZERO);
+        }
+    }
+
+    /// Create or remove section headers so there is one for each section in the data.
+    ///
+    /// Returns `true` if headers were added or removed.
+    fn update_headers(&mut self, data: &impl GridIter<T>) -> bool {
+        let build = match &self.section_header {
+            Some(build) => build,
+            None => return false,
+        };
+        let sections = section_starts(data);
+        let mut changed = self.headers.len() != sections.len();
+        self.headers.truncate(sections.len());
+        for (i, (_, id)) in sections.into_iter().enumerate() {
+            match self.headers.get_mut(i) {
+                Some((current, _)) if *current == id => (),
+                Some(header) => {
+                    *header = (id, WidgetPod::new(build(id)));
+                    changed = true;
+                }
+                None => self.headers.push((id, WidgetPod::new(build(id)))),
+            }
+        }
+        changed
+    }
+}
+
+/// Identifies the section a grid item belongs to.
+pub type SectionId = u64;
+
+type HeaderBuilder = Box<dyn Fn(SectionId) -> Box<dyn Widget<SectionId>>>;
+type HeaderPod = WidgetPod<SectionId, Box<dyn Widget<SectionId>>>;
+
+/// Returns the index of the first item of each section along with its section.
+fn section_starts<T>(data: &impl GridIter<T>) -> Vec<(usize, SectionId)> {
+    let mut starts = Vec::new();
+    let mut current = None;
+    data.for_each(|_, idx| {
+        let section = data.section(idx);
+        if section != current {
+            if let Some(id) = section {
+                starts.push((idx, id));
+            }
+            current = section;
+        }
+    });
+    starts
+}
+
+/// This iterator enables writing GridView widget for any `Data`.
+pub trait GridIter<T>: Data {
+    /// Iterate over each data child.
+    fn for_each(&self, cb: impl FnMut(&T, usize));
+
+    /// Iterate over each data child and keep track of changed data to update self.
+    fn for_each_mut(&mut self, cb: impl FnMut(&mut T, usize));
+
+    /// Return the data length.
+    fn data_len(&self) -> usize;
+
+    /// Return any child data to be used to get child size.
+    fn child_data(&self) -> Option<T>;
+
+    /// Move the child at `from` so that it ends up at index `to`, shifting the
+    /// children in between.
+    ///
+    /// This is used to reorder items that are dragged within the grid. Returns
+    /// whether the child was moved. The default implementation does nothing and
+    /// returns `false`, for collections that can't be reordered.
+    fn move_item(&mut self, _from: usize, _to: usize) -> bool {
+        false
+    }
+
+    /// Return whether the child at `index` is disabled. Disabled children don't
+    /// receive input and are painted dimmed.
+    fn is_disabled(&self, _index: usize) -> bool {
+        false
+    }
+
+    /// Return the section the child at `index` belongs to, if the data is
+    /// grouped into sections.
+    fn section(&self, _index: usize) -> Option<SectionId> {
+        None
+    }
+
+    /// Return the kind of the child at `index`, which picks the closure from
+    /// [`GridView::with_builders`] that builds its widget.
+    fn kind(&self, _index: usize) -> u32 {
+        0
+    }
+
+    // fn row(&self, cb: impl FnMut(&T, usize), row_len: usize);
+    // fn row_mut(&mut self, cb: impl FnMut(&mut T, usize), row_len: usize);
+}
+
+impl<T: Data> GridIter<T> for Arc<Vec<T>> {
+    // fn row(&self, mut cb: impl FnMut(&T, usize), row_len: usize) {
+    //     let chunks_len = row_len;
+    //     for (i, row) in self.chunks(chunks_len).enumerate() {
+    //         for (j, item) in row.iter().enumerate() {
+    //             cb(item, i * chunks_len + j)
+    //         }
+    //     }
+    // }
+    // fn row_mut(&mut self, mut cb: impl FnMut(&mut T, usize), row_len: usize) {
+    //     let chunks_len = row_len;
+    //     let mut new_data = Vec::with_capacity(self.data_len());
+    //     let mut any_changed = false;
+
+    //     for (i, row) in self.chunks(chunks_len).enumerate() {
+    //         for (j, item) in row.iter().enumerate() {
+    //             let mut d = item.to_owned();
+    //             cb(&mut d, i * chunks_len + j);
+
+    //             if !any_changed && !item.same(&d) {
+    //                 any_changed = true;
+    //             }
+    //             new_data.push(d);
+    //         }
+    //     }
+
+    //     if any_changed {
+    //         *self = Arc::new(new_data);
+    //     }
+    // }
+
+    fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
+        for (i, item) in self.iter().enumerate() {
+            cb(item, i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut T, usize)) {
+        let mut new_data = Vec::with_capacity(self.data_len());
+        let mut any_changed = false;
+
+        for (i, item) in self.iter().enumerate() {
+            let mut d = item.to_owned();
+            cb(&mut d, i);
+
+            if !any_changed && !item.same(&d) {
+                any_changed = true;
+            }
+            new_data.push(d);
+        }
+
+        if any_changed {
+            *self = Arc::new(new_data);
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.len()
+    }
+
+    fn child_data(&self) -> Option<T> {
+        // TODO MUST FIX THIS - no panics
+        Some(self.iter().next().unwrap().clone())
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        if from < self.len() && to < self.len() {
+            let items = Arc::make_mut(self);
+            let item = items.remove(from);
+            items.insert(to, item);
+            return true;
+        }
+        false
+    }
+}
+
+impl<T: Data> GridIter<T> for Vector<T> {
+    fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
+        for (i, item) in self.iter().enumerate() {
+            cb(item, i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut T, usize)) {
+        for (i, item) in self.iter_mut().enumerate() {
+            cb(item, i);
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.len()
+    }
+
+    fn child_data(&self) -> Option<T> {
+        Some(self.iter().next().unwrap().clone())
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        if from < self.len() && to < self.len() {
+            let item = self.remove(from);
+            self.insert(to, item);
+            return true;
+        }
+        false
+    }
+}
+
+/// Items together with data shared by all of them, like a zoom level or a theme
+/// variant.
+///
+/// Each item gets the shared data along with its own item as `(S, T)`. Changes
+/// an item makes to the shared data are written back, so they reach every item.
+impl<S: Data, T: Data, L: GridIter<T>> GridIter<(S, T)> for (S, L) {
+    fn for_each(&self, mut cb: impl FnMut(&(S, T), usize)) {
+        let (shared, items) = self;
+        items.for_each(|item, i| {
+            let d = (shared.to_owned(), item.to_owned());
+            cb(&d, i);
+        });
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut (S, T), usize)) {
+        let (shared, items) = self;
+        items.for_each_mut(|item, i| {
+            let mut d = (shared.to_owned(), item.to_owned());
+            cb(&mut d, i);
+
+            let (new_shared, new_item) = d;
+            if !shared.same(&new_shared) {
+                *shared = new_shared;
+            }
+            if !item.same(&new_item) {
+                *item = new_item;
+            }
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.1.data_len()
+    }
+
+    fn child_data(&self) -> Option<(S, T)> {
+        let (shared, items) = self;
+        items.child_data().map(|item| (shared.to_owned(), item))
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        self.1.move_item(from, to)
+    }
+
+    fn is_disabled(&self, index: usize) -> bool {
+        self.1.is_disabled(index)
+    }
+
+    fn kind(&self, index: usize) -> u32 {
+        self.1.kind(index)
+    }
+
+    fn section(&self, index: usize) -> Option<SectionId> {
+        self.1.section(index)
+    }
+}
+
+/// A list of items where each item is tagged with the section it belongs to.
+///
+/// Items of the same section are expected to be next to each other. Use
+/// [`GridView::with_section_header`] to show a header before each section.
+#[derive(Clone)]
+pub struct Sectioned<T> {
+    items: Arc<Vec<(SectionId, T)>>,
+}
+
+impl<T: Data> Sectioned<T> {
+    /// Create a new sectioned list from items tagged with their section.
+    pub fn new(items: Vec<(SectionId, T)>) -> Self {
+        Sectioned {
+            items: Arc::new(items),
+        }
+    }
+
+    /// Add an item to the end of the list.
+    pub fn push(&mut self, section: SectionId, item: T) {
+        Arc::make_mut(&mut self.items).push((section, item));
+    }
+}
+
+impl<T: Data> Data for Sectioned<T> {
+    fn same(&self, other: &Self) -> bool {
+        self.items.same(&other.items)
+    }
+}
+
+impl<T: Data> GridIter<T> for Sectioned<T> {
+    fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
+        for (i, (_, item)) in self.items.iter().enumerate() {
+            cb(item, i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut T, usize)) {
+        let mut new_data = Vec::with_capacity(self.data_len());
+        let mut any_changed = false;
+
+        for (i, (section, item)) in self.items.iter().enumerate() {
+            let mut d = item.to_owned();
+            cb(&mut d, i);
+
+            if !any_changed && !item.same(&d) {
+                any_changed = true;
+            }
+            new_data.push((*section, d));
+        }
+
+        if any_changed {
+            self.items = Arc::new(new_data);
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn child_data(&self) -> Option<T> {
+        self.items.first().map(|(_, item)| item.clone())
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        if from < self.items.len() && to < self.items.len() {
+            let items = Arc::make_mut(&mut self.items);
+            let item = items.remove(from);
+            items.insert(to, item);
+            return true;
+        }
+        false
+    }
+
+    fn section(&self, index: usize) -> Option<SectionId> {
+        self.items.get(index).map(|(section, _)| *section)
+    }
+}
+
+/// A list that is loaded a page at a time, for example from the network.
+///
+/// The grid gets the known total number of items, with the items of pages that
+/// haven't loaded yet as `None`. Wrap the item widget in druid's `Maybe` to show a
+/// placeholder for them.
+#[derive(Clone)]
+pub struct Paged<T> {
+    len: usize,
+    page_size: usize,
+    pages: OrdMap<usize, Arc<Vec<T>>>,
+}
+
+impl<T: Data> Paged<T> {
+    /// Create a list of `len` items split into pages of `page_size` items, with
+    /// no pages loaded yet.
+    pub fn new(len: usize, page_size: usize) -> Self {
+        Paged {
+            len,
+            page_size: page_size.max(1),
+            pages: OrdMap::new(),
+        }
+    }
+
+    /// Store the items of a page once it has loaded.
+    pub fn set_page(&mut self, page: usize, items: Vec<T>) {
+        self.pages.insert(page, Arc::new(items));
+    }
+
+    /// Whether the item at `index` is waiting for its page to load.
+    pub fn is_pending(&self, index: usize) -> bool {
+        index < self.len && self.get(index).is_none()
+    }
+
+    /// The page the item at `index` is on.
+    pub fn page_of(&self, index: usize) -> usize {
+        index / self.page_size
+    }
+
+    fn get(&self, index: usize) -> Option<T> {
+        let page = self.pages.get(&self.page_of(index))?;
+        page.get(index % self.page_size).cloned()
+    }
+}
+
+impl<T: Data> Data for Paged<T> {
+    fn same(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self.page_size == other.page_size
+            && self.pages.same(&other.pages)
+    }
+}
+
+impl<T: Data> GridIter<Option<T>> for Paged<T> {
+    fn for_each(&self, mut cb: impl FnMut(&Option<T>, usize)) {
+        for i in 0..self.len {
+            cb(&self.get(i), i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut Option<T>, usize)) {
+        for i in 0..self.len {
+            let old = self.get(i);
+            let mut item = old.clone();
+            cb(&mut item, i);
+
+            // only loaded items can change, pending ones wait for their page
+            if let (Some(old), Some(new)) = (old, item) {
+                if !old.same(&new) {
+                    let page = self.page_of(i);
+                    if let Some(items) = self.pages.get_mut(&page) {
+                        Arc::make_mut(items)[i % self.page_size] = new;
+                    }
+                }
+            }
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.len
+    }
+
+    fn child_data(&self) -> Option<Option<T>> {
+        (self.len > 0).then(|| self.get(0))
+    }
+}
+
+impl<C: Data, T: GridIter<C>> Widget<T> for GridView<C, T> {
+    fn event(
+        &mut self,
+        ctx: &mut druid::EventCtx,
+        event: &druid::Event,
+        data: &mut T,
+        env: &druid::Env,
+    ) {
+        if let Event::Notification(note) = event {
+            self.scroll_notification(ctx, note);
+            return;
+        }
+        if let Event::Command(cmd) = event {
+            if let Some(offset) = cmd.get(SET_SCROLL_OFFSET) {
+                self.scroll_to_offset(ctx, *offset);
+                ctx.set_handled();
+                return;
+            }
+            if cmd.is(RUN_DEFERRED) {
+                self.deferred = false;
+                self.run_deferred(ctx);
+                self.write_column_count(data);
+                self.write_scroll_offset(data);
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Event::Timer(token) = event {
+            if let Some((long_press_token, idx, _)) = self.long_press_timer {
+                if long_press_token == *token {
+                    if let Some(selection) = self.long_pressed(idx) {
+                        self.set_selection(ctx, selection);
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            if let Some((tooltip_token, idx, pos)) = self.tooltip_timer {
+                if tooltip_token == *token {
+                    self.tooltip_timer = None;
+                    self.tooltip_shown = Some(idx);
+                    ctx.submit_command(SHOW_TOOLTIP.with((idx, pos)));
+                    ctx.set_handled();
+                    return;
+                }
+            }
+        }
+
+        if let Event::AnimFrame(interval) = event {
+            if self.is_appearing() {
+                let elapsed = *interval as f64 / 1e9;
+                for t in &mut self.appear {
+                    *t += elapsed;
+                }
+                ctx.request_paint();
+                if self.is_appearing() {
+                    ctx.request_anim_frame();
+                }
+            }
+            if let Some(ripple) = &mut self.ripple {
+                ripple.t += *interval as f64 / 1e9;
+                ctx.request_paint();
+                if ripple.t < RIPPLE_DURATION {
+                    ctx.request_anim_frame();
+                } else {
+                    self.ripple = None;
+                }
+            }
+        }
+
+        // disabled items don't receive any input
+        let is_input = is_user_input(event);
+        let cell_envs = self.cell_envs(env);
+        let disabled = &self.disabled;
+        let mut children = self.children.iter_mut();
+        data.for_each_mut(|child_data, idx| {
+            if let Some(child) = children.next() {
+                if !(is_input && disabled.get(idx) == Some(&true)) {
+                    let env = cell_envs.get(idx, env);
+                    child.event(ctx, event, child_data, env);
+                }
+            }
+        });
+        for (id, header) in self.headers.iter_mut() {
+            let mut header_data = *id;
+            header.event(ctx, event, &mut header_data, env);
+        }
+        if let Some(row) = &mut self.aggregate_row {
+            // the row is painted shifted to stay in view, the mouse has to follow
+            let event = shift_mouse(event, -self.aggregate_shift);
+            row.event(ctx, &event, data, env);
+        }
+
+        if let Event::MouseMove(mouse) = event {
+            let hovered = if ctx.is_hot() {
+                self.cell_at(mouse.pos)
+            } else {
+                None
+            };
+            for rect in self.set_hovered(hovered) {
+                ctx.request_paint_rect(rect);
+            }
+            self.tooltip_mouse_move(ctx, hovered, mouse.window_pos);
+        }
+        match event {
+            Event::MouseDown(mouse) if self.wants_focus() => {
+                if let Some(idx) = self.enabled_cell_at(mouse.pos) {
+                    if self.focused != Some(idx)
+                        && self.cell_state_key.is_some()
+                    {
+                        ctx.request_paint();
+                    }
+                    self.focused = Some(idx);
+                    ctx.request_focus();
+                }
+            }
+            // this runs before `click_event`, so the callback can handle Enter
+            // and Space to keep them from activating the item
+            Event::KeyDown(key) if ctx.is_focused() => {
+                if let (Some(focused), Some(cb)) =
+                    (self.focused, &self.on_cell_key)
+                {
+                    data.for_each_mut(|item, idx| {
+                        if idx == focused {
+                            cb(ctx, key, item, idx);
+                        }
+                    });
+                }
+            }
+            _ => (),
+        }
+        if self.long_press.is_some() {
+            self.long_press_event(ctx, event);
+        }
+        if self.on_item_click.is_some() || self.ripple_color.is_some() {
+            self.click_event(ctx, event, data, env);
+        }
+        if self.wants_focus() {
+            self.navigate_event(ctx, event);
+        }
+        if self.on_column_resize.is_some() {
+            self.resize_event(ctx, event, env);
+        }
+        if self.ordered_selection.is_some() {
+            self.pick_event(ctx, event);
+        }
+        if self.can_drag() {
+            self.drag_event(ctx, event, data);
+        }
+        if self.marquee_selection {
+            self.marquee_event(ctx, event);
+        }
+        self.write_selection(data);
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut druid::LifeCycleCtx,
+        event: &druid::LifeCycle,
+        data: &T,
+        env: &druid::Env,
+    ) {
+        if let LifeCycle::HotChanged(false) = event {
+            for rect in self.set_hovered(None) {
+                ctx.request_paint_rect(rect);
+            }
+            self.tooltip_timer = None;
+            if self.tooltip_shown.take().is_some() {
+                ctx.submit_command(HIDE_TOOLTIP);
+            }
+        }
+
+        if let LifeCycle::ViewContextChanged(view) = event {
+            if self.set_visible(view.clip, ctx.size()) {
+                if let Some(cmd) = self.defer(ctx.widget_id()) {
+                    ctx.submit_command(cmd);
+                }
+            }
+            ctx.request_paint();
+        }
+
+        if let LifeCycle::BuildFocusChain = event {
+            if self.wants_focus() {
+                ctx.register_for_focus();
+            }
+        }
+
+        if let LifeCycle::WidgetAdded = event {
+            let children_changed = self.update_child_count(data, env);
+            if self.update_headers(data) || children_changed {
+                ctx.children_changed();
+            }
+            if self.is_appearing() {
+                ctx.request_anim_frame();
+            }
+            self.update_disabled(data);
+            if let Some(build) = &self.aggregate_builder {
+                self.aggregate_row = Some(WidgetPod::new(build(data)));
+            }
+        }
+
+        let cell_envs = self.cell_envs(env);
+        let mut children = self.children.iter_mut();
+        data.for_each(|child_data, idx| {
+            if let Some(child) = children.next() {
+                let env = cell_envs.get(idx, env);
+                child.lifecycle(ctx, event, child_data, env);
+            }
+        });
+        for (id, header) in self.headers.iter_mut() {
+            header.lifecycle(ctx, event, id, env);
+        }
+        if let Some(row) = &mut self.aggregate_row {
+            row.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut druid::UpdateCtx,
+        _old_data: &T,
+        data: &T,
+        env: &druid::Env,
+    ) {
+        let old_len = self.children.len();
+        let children_changed = self.update_child_count(data, env);
+        let headers_changed = self.update_headers(data);
+
+        // newly built children get their data with `WidgetAdded` and mustn't be
+        // updated before that, while widgets reused from the cache have already
+        // been added and need the data of their new item
+        let cell_envs = self.cell_envs(env);
+        let mut children = self.children.iter_mut();
+        data.for_each(|child_data, idx| {
+            let child = children.next().filter(|child| child.is_initialized());
+            if let Some(child) = child {
+                let env = cell_envs.get(idx, env);
+                child.update(ctx, child_data, env);
+            }
+        });
+        for (id, header) in self.headers.iter_mut() {
+            if header.is_initialized() {
+                header.update(ctx, id, env);
+            }
+        }
+
+        if headers_changed || children_changed {
+            ctx.children_changed();
+        }
+        if self.is_appearing() {
+            ctx.request_anim_frame();
+        }
+        let message = announcement(old_len, self.children.len());
+        if let Some(message) = message.filter(|_| self.live_announcements) {
+            ctx.submit_command(LIVE_ANNOUNCEMENT.with(message));
+        }
+        self.update_disabled(data);
+        if let Some(row) = &mut self.aggregate_row {
+            row.update(ctx, data, env);
+        }
+        let len = self.children.len();
+        // the indices of a drag are stale once items come or go
+        if let Some(drag) = self.drag.as_mut().filter(|_| len != old_len) {
+            drag.cancelled = true;
+            drag.target = None;
+            ctx.request_paint();
+        }
+        self.selection.retain(|idx| *idx < len);
+        self.focused = self.focused.filter(|idx| *idx < len);
+        if let Some(picked) = self.read_selection(data) {
+            let picked: Vec<usize> = in_pick_order(&self.selection, &picked)
+                .into_iter()
+                .filter(|idx| *idx < len)
+                .collect();
+            if picked != self.selection {
+                self.selection = picked;
+                ctx.request_paint();
+            }
+        }
+        self.selection_mode &= !self.selection.is_empty();
+
+        // spacing can come from the env, so a theme change has to move the items
+        if ctx.env_key_changed(&self.vertical_spacing)
+            || ctx.env_key_changed(&self.horizontal_spacing)
+            || ctx.env_key_changed(&self.cell_margin)
+        {
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut druid::LayoutCtx,
+        bc: &druid::BoxConstraints,
+        data: &T,
+        env: &druid::Env,
+    ) -> druid::Size {
+        // only rows/columns have column tracks and stripes
+        self.tracks.clear();
+        self.rows.clear();
+        let my_size = match self.arrangement {
+            Arrangement::Grid => self.layout_grid(ctx, bc, data, env),
+            Arrangement::Radial {
+                radius,
+                start_angle,
+            } => self.layout_radial(ctx, bc, data, env, radius, start_angle),
+            Arrangement::Custom(_) => self.layout_custom(ctx, bc, data, env),
+            Arrangement::Path(_) => self.layout_path(ctx, bc, data, env),
+        };
+        if !matches!(self.arrangement, Arrangement::Grid) {
+            // only rows/columns are laid out incrementally
+            self.laid_out = self.children.len();
+        }
+
+        self.request_initial_scroll();
+        self.incremental_pass = false;
+        if self.column_count_sink.is_some() {
+            let count = match self.arrangement {
+                // in flow mode each row/column fits a different number of items
+                Arrangement::Grid if self.intrinsic_width => self.longest_line,
+                Arrangement::Grid => {
+                    self.preferred_columns(self.axis.minor(bc.max()), env)
+                }
+                _ => 0,
+            };
+            self.set_column_count(count);
+        }
+        let metrics_work = self.metrics_target.is_some()
+            && self.metrics_sent != Some(self.metrics(my_size));
+        let viewport_work = self.set_visible(self.visible, my_size);
+        if viewport_work
+            || self.scroll_request.is_some()
+            || self.offset_request.is_some()
+            || self.pending_bc.is_some()
+            || metrics_work
+            || (self.column_count_sink.is_some() && !self.column_count_written)
+        {
+            if let Some(cmd) = self.defer(ctx.widget_id()) {
+                ctx.submit_command(cmd);
+            }
+        }
+
+        my_size
+    }
+
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &T, env: &druid::Env) {
+        if let Some(color) = &self.row_stripes {
+            let color = color.resolve(env);
+            let minor_len = self.axis.minor(ctx.size());
+            let region = ctx.region().bounding_box();
+            for stripe in self.stripe_rects(minor_len, region) {
+                ctx.fill(stripe, &color);
+            }
+        }
+
+        for (id, header) in self.headers.iter_mut() {
+            header.paint(ctx, id, env);
+        }
+
+        let cell_envs = self.cell_envs(env);
+        let cell_clip = self.cell_clip;
+        let disabled = &self.disabled;
+        let fade_color = self.fade_color.resolve(env);
+        let dim = fade_color.with_alpha(0.5);
+        let appear = &self.appear;
+        let offsets = self.lift_offsets();
+        let transforms: Vec<_> = (0..self.children.len())
+            .map(|idx| self.paint_transform(idx, &offsets))
+            .collect();
+        let lifted = self.drag.as_ref().map(|drag| drag.source);
+        let lifted = lifted.filter(|_| !offsets.is_empty());
+        let paint_child = |ctx: &mut druid::PaintCtx,
+                           child: &mut WidgetPod<C, Box<dyn Widget<C>>>,
+                           child_data: &C,
+                           idx: usize| {
+            let env = cell_envs.get(idx, env);
+            let progress = appear.get(idx).map_or(1., |t| t / APPEAR_DURATION);
+            let rect = child.layout_rect();
+            let transform = transforms[idx];
+            let moved = transform != Affine::IDENTITY;
+            if cell_clip || moved {
+                ctx.with_save(|ctx| {
+                    ctx.transform(transform);
+                    if cell_clip {
+                        ctx.clip(child.layout_rect());
+                    }
+                    // the invalid region is where the item is laid out, not
+                    // where it is painted
+                    if moved {
+                        child.paint_always(ctx, child_data, env);
+                    } else {
+                        child.paint(ctx, child_data, env);
+                    }
+                });
+            } else {
+                child.paint(ctx, child_data, env);
+            }
+            let painted = transform.transform_rect_bbox(rect);
+            if disabled.get(idx) == Some(&true) {
+                ctx.fill(painted, &dim);
+            }
+            // items fade in by painting less and less of the fade color over them
+            if progress < 1. {
+                let hidden = fade_color.with_alpha(1. - progress.max(0.));
+                ctx.fill(painted, &hidden);
+            }
+        };
+        if self.z_order.is_some() {
+            let mut items = Vec::with_capacity(self.children.len());
+            data.for_each(|child_data, _| items.push(child_data.clone()));
+            for idx in self.paint_order() {
+                let child = self.children.get_mut(idx);
+                let child = child.filter(|_| lifted != Some(idx));
+                if let (Some(child), Some(child_data)) = (child, items.get(idx))
+                {
+                    paint_child(ctx, child, child_data, idx);
+                }
+            }
+        } else {
+            let mut children = self.children.iter_mut();
+            data.for_each(|child_data, idx| {
+                let child = children.next();
+                if let Some(child) = child.filter(|_| lifted != Some(idx)) {
+                    paint_child(ctx, child, child_data, idx);
+                }
+            });
+        }
+
+        let selection_color = env.get(theme::PRIMARY_LIGHT);
+        for child in self.selection.iter().filter_map(|i| self.children.get(*i))
+        {
+            ctx.stroke(child.layout_rect(), &selection_color, 2.);
+        }
+        if let Some((start, end)) = self.marquee {
+            let marquee = Rect::from_points(start, end);
+            ctx.fill(
+                marquee,
+                &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+                    .with_alpha(0.3),
+            );
+            ctx.stroke(marquee, &selection_color, 1.);
+        }
+
+        if let Some(color) = &self.drop_target_highlight {
+            let target =
+                self.drop_target().and_then(|idx| self.children.get(idx));
+            if let Some(target) = target {
+                ctx.fill(target.layout_rect(), &color.resolve(env));
+            }
+        }
+
+        // the lifted item goes on top of everything else in the grid
+        if let (Some(lifted), Some(shadow)) = (lifted, &self.drag_lift) {
+            let shadow = shadow.resolve(env);
+            let children = &mut self.children;
+            data.for_each(|child_data, idx| {
+                if let Some(child) =
+                    children.get_mut(idx).filter(|_| idx == lifted)
+                {
+                    let rect = child.layout_rect() + offsets[idx];
+                    ctx.blurred_rect(rect, LIFT_BLUR, &shadow);
+                    paint_child(ctx, child, child_data, idx);
+                }
+            });
+        }
+
+        if let (Some(ripple), Some(color)) = (&self.ripple, &self.ripple_color)
+        {
+            if let Some(child) = self.children.get(ripple.idx) {
+                // the ripple grows to reach the item's farthest corner and fades
+                // out as it does
+                let rect = child.layout_rect();
+                let reach = ripple_reach(rect, ripple.center);
+                let progress = (ripple.t / RIPPLE_DURATION).min(1.);
+                let color = color.resolve(env);
+                let alpha = color.as_rgba().3 * (1. - progress);
+                let circle = Circle::new(ripple.center, reach * progress);
+                ctx.with_save(|ctx| {
+                    ctx.clip(rect);
+                    ctx.fill(circle, &color.with_alpha(alpha));
+                });
+            }
+        }
+
+        self.paint_edge_fade(ctx, env);
+        self.paint_scroll_shadows(ctx, env);
+
+        if let Some(row) = &mut self.aggregate_row {
+            // keep the row at the end of the visible area, but not before its own
+            // position in the grid, so it stays in view while scrolling
+            let axis = self.axis;
+            let row_rect = row.layout_rect();
+            let row_start = axis.major_pos(row_rect.origin());
+            let row_end = axis.major_pos(Point::new(row_rect.x1, row_rect.y1));
+            let view_end =
+                axis.major_pos(Point::new(self.viewport.x1, self.viewport.y1));
+            let shift = if self.viewport.area() > 0. {
+                (view_end - row_end).min(0.).max(-row_start)
+            } else {
+                0.
+            };
+            self.aggregate_shift = axis.pack(shift, 0.).into();
+            let shift = self.aggregate_shift;
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate(shift));
+                row.paint_always(ctx, data, env);
+            });
+        }
+    }
+}
+/// Tracks where the next item goes while laying out rows/columns.
+struct Cursor {
+    /// Where the current row/column starts on the major axis.
+    major: f64,
+    /// Where the next item starts on the minor axis.
+    minor: f64,
+    /// The position of the next item within its row/column.
+    column: usize,
+    /// The largest major size found in the current row/column so far.
+    line_major: f64,
+    /// The major size of the first row/column plus spacing.
+    row_pitch: Option<f64>,
+    margin: f64,
+    major_spacing: f64,
+}
+
+impl Cursor {
+    fn new(margin: f64, major_spacing: f64) -> Self {
+        Cursor {
+            major: margin,
+            minor: margin,
+            column: 0,
+            line_major: 0.,
+            row_pitch: None,
+            margin,
+            major_spacing,
+        }
+    }
+
+    /// Move to the start of the next row/column.
+    fn next_line(&mut self) {
+        let pitch = self.line_major + self.major_spacing;
+        self.row_pitch.get_or_insert(pitch);
+        self.major += pitch;
+        self.minor = self.margin;
+        self.column = 0;
+        self.line_major = 0.;
+    }
+
+    /// Whether an item of `minor_size` fits before `minor_limit` in the current
+    /// row/column. An item always fits in an empty row/column.
+    fn fits(&self, minor_size: f64, minor_limit: f64) -> bool {
+        self.column == 0 || self.minor + minor_size <= minor_limit
+    }
+
+    /// The largest length up to `major_len` that fits a whole number of
+    /// rows/columns, but at least one, with their spacing and the margins.
+    fn snap(&self, major_len: f64) -> f64 {
+        match self.row_pitch {
+            Some(pitch) if pitch > 0. => {
+                // n rows take n * pitch, less the spacing after the last row
+                let edges = 2. * self.margin - self.major_spacing;
+                let rows = ((major_len - edges) / pitch).floor().max(1.);
+                rows * pitch + edges
+            }
+            _ => major_len,
+        }
+    }
+}
+
+/// The size of a grid of rows/columns whose content covers `content_size`.
+///
+/// When the major axis is bounded, for example when the grid is a flex child,
+/// the grid takes the whole allotment, shortened by `fit`, and lets the items
+/// wrap within it instead of shrinking to the content.
+fn grid_size(
+    axis: Axis,
+    bc: &BoxConstraints,
+    content_size: Size,
+    fit: impl FnOnce(f64) -> f64,
+) -> Size {
+    let major_len = axis.major(bc.max());
+    if major_len.is_finite() {
+        let major_len = fit(major_len);
+        bc.constrain(Size::from(axis.pack(major_len, axis.minor(content_size))))
+    } else {
+        bc.constrain(content_size)
+    }
+}
+
+/// The number of items on the row/column with the most items, given the
+/// row/column of each item in order.
+fn longest_line(line_of: &[usize]) -> usize {
+    let mut longest = 0;
+    let mut start = 0;
+    for (idx, line) in line_of.iter().enumerate() {
+        if *line != line_of[start] {
+            start = idx;
+        }
+        longest = longest.max(idx + 1 - start);
+    }
+    longest
+}
+
+/// How far the items of a row/column ending at `line_end` on the minor axis move
+/// to line up with `trailing_edge`.
+fn trailing_shift(axis: Axis, trailing_edge: f64, line_end: f64) -> Vec2 {
+    Vec2::from(axis.pack(0., trailing_edge - line_end))
+}
+
+/// The part of the grid an item laid out at `cell` paints over, when its widget
+/// paints over `paint_rect`. Items clipped to their cell can't paint past it.
+fn paint_bounds(cell_clip: bool, cell: Rect, paint_rect: Rect) -> Rect {
+    if cell_clip {
+        cell
+    } else {
+        paint_rect
+    }
+}
+
+/// Split `minor_len`, minus the spacing between tracks, into tracks sized in
+/// proportion to `weights`.
+fn track_sizes(
+    weights: &[f64],
+    minor_len: f64,
+    spacing: f64,
+) -> Option<Vec<f64>> {
+    let total: f64 = weights.iter().sum();
+    if weights.is_empty() || total <= 0. || !minor_len.is_finite() {
+        return None;
+    }
+    let spacing = spacing * (weights.len() - 1) as f64;
+    let available = (minor_len - spacing).max(0.);
+    Some(weights.iter().map(|w| available * w / total).collect())
+}
+
+/// The point `distance` along a path made of `segments` and their lengths.
+fn point_along(segments: &[(PathSeg, f64)], distance: f64) -> Point {
+    let mut remaining = distance;
+    for (seg, len) in segments {
+        if remaining <= *len {
+            return seg.eval(seg.inv_arclen(remaining, ARCLEN_ACCURACY));
+        }
+        remaining -= len;
+    }
+    // rounding can leave the last item just past the end
+    segments.last().map_or(Point::ZERO, |(seg, _)| seg.end())
+}
+
+/// Where the center of item `idx` of `count` items spread evenly around a circle
+/// lands, starting at `start_angle`.
+fn radial_center(
+    center: Point,
+    radius: f64,
+    start_angle: f64,
+    idx: usize,
+    count: usize,
+) -> Point {
+    let angle = start_angle + 2. * PI / count.max(1) as f64 * idx as f64;
+    Point::new(
+        center.x + radius * angle.cos(),
+        center.y + radius * angle.sin(),
+    )
+}
+
+/// The index of the item at `idx` after the item at `from` moved to `to`.
+fn moved_index(idx: usize, from: usize, to: usize) -> usize {
+    if idx == from {
+        to
+    } else if from < idx && idx <= to {
+        idx - 1
+    } else if to <= idx && idx < from {
+        idx + 1
+    } else {
+        idx
+    }
+}
+
+/// Move the entry at `from` to `to`, if both are in `entries`.
+fn move_entry<E>(entries: &mut Vec<E>, from: usize, to: usize) {
+    if from < entries.len() && to < entries.len() {
+        let entry = entries.remove(from);
+        entries.insert(to, entry);
+    }
+}
+
+/// How far a ripple starting at `center` has to grow to cover all of `rect`.
+fn ripple_reach(rect: Rect, center: Point) -> f64 {
+    [
+        Point::new(rect.x0, rect.y0),
+        Point::new(rect.x1, rect.y0),
+        Point::new(rect.x0, rect.y1),
+        Point::new(rect.x1, rect.y1),
+    ]
+    .iter()
+    .map(|corner| corner.distance(center))
+    .fold(0., f64::max)
+}
+
+/// `pos` moved inside `bounds`.
+fn clamp_point(pos: Point, bounds: Rect) -> Point {
+    Point::new(
+        pos.x.clamp(bounds.x0, bounds.x1),
+        pos.y.clamp(bounds.y0, bounds.y1),
+    )
+}
+
+/// The index of the rect in `rects` that contains `pos`.
+///
+/// If none does, rects smaller than `min_target` are grown around their center
+/// to that size, and the closest of the grown rects containing `pos` wins.
+fn hit_test(
+    rects: impl Iterator<Item = Rect> + Clone,
+    pos: Point,
+    min_target: Size,
+) -> Option<usize> {
+    let exact = rects.clone().position(|rect| rect.contains(pos));
+    if exact.is_some() || min_target == Size::ZERO {
+        return exact;
+    }
+    let distance = |rect: Rect| (rect.center() - pos).hypot();
+    rects
+        .enumerate()
+        .filter(|(_, rect)| {
+            let grow_x = (min_target.width - rect.width()).max(0.) / 2.;
+            let grow_y = (min_target.height - rect.height()).max(0.) / 2.;
+            rect.inflate(grow_x, grow_y).contains(pos)
+        })
+        .min_by(|(_, a), (_, b)| {
+            distance(*a)
+                .partial_cmp(&distance(*b))
+                .unwrap_or(Ordering::Equal)
+        })
+        .map(|(idx, _)| idx)
+}
+
+/// The indices in `picked`, with those in `order` first and in that order,
+/// followed by the others in index order.
+fn in_pick_order(order: &[usize], picked: &OrdSet<usize>) -> Vec<usize> {
+    let mut ordered: Vec<usize> = order
+        .iter()
+        .copied()
+        .filter(|idx| picked.contains(idx))
+        .collect();
+    ordered.extend(picked.iter().filter(|idx| !order.contains(idx)));
+    ordered
+}
+
+/// The indices of the `rects` a marquee overlaps. Touching an edge isn't
+/// enough.
+fn marquee_hits(
+    marquee: Rect,
+    rects: impl Iterator<Item = Rect>,
+) -> impl Iterator<Item = usize> {
+    rects
+        .enumerate()
+        .filter(move |(_, rect)| rect.intersect(marquee).area() > 0.)
+        .map(|(idx, _)| idx)
+}
+
+/// The message announcing that the number of items went from `old_len` to `len`,
+/// if it changed.
+fn announcement(old_len: usize, len: usize) -> Option<String> {
+    let (count, change) = match len.cmp(&old_len) {
+        Ordering::Greater => (len - old_len, "added"),
+        Ordering::Less => (old_len - len, "removed"),
+        Ordering::Equal => return None,
+    };
+    let items = if count == 1 { "item" } else { "items" };
+    Some(format!("{} {} {}", count, items, change))
+}
+
+/// Constraints that keep the minor size of an item at `size` and clamp its major
+/// size so the aspect ratio is within `(min_ratio, max_ratio)`, or `None` if it
+/// already is.
+fn ratio_constraints(
+    axis: Axis,
+    (min_ratio, max_ratio): (f64, f64),
+    size: Size,
+) -> Option<BoxConstraints> {
+    let minor = axis.minor(size);
+    let (min_major, max_major) = match axis {
+        Axis::Vertical => (minor / max_ratio, minor / min_ratio),
+        Axis::Horizontal => (minor * min_ratio, minor * max_ratio),
+    };
+    let major = axis.major(size);
+    if major >= min_major && major <= max_major {
+        return None;
+    }
+    Some(BoxConstraints::new(
+        Size::from(axis.pack(min_major, minor)),
+        Size::from(axis.pack(max_major, minor)),
+    ))
+}
+
+/// Whether `key` activates the focused item, like a click does.
+fn is_activation_key(key: &KbKey) -> bool {
+    match key {
+        KbKey::Enter => true,
+        KbKey::Character(c) => c == " ",
+        _ => false,
+    }
+}
+
+/// `event` with its mouse position moved by `offset`.
+fn shift_mouse(event: &Event, offset: Vec2) -> Event {
+    let mut event = event.clone();
+    if let Event::MouseDown(mouse)
+    | Event::MouseUp(mouse)
+    | Event::MouseMove(mouse)
+    | Event::Wheel(mouse) = &mut event
+    {
+        mouse.pos += offset;
+    }
+    event
+}
+
+/// Whether `event` comes from the user, as opposed to commands, timers and the like.
+fn is_user_input(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::Paste(_)
+            | Event::Zoom(_)
+    )
+}
+
+/// Generate constraints with new values on the major axis.
+fn constraints(
+    axis: Axis,
+    bc: &BoxConstraints,
+    min_major: f64,
+    major: f64,
+) -> BoxConstraints {
+    match axis {
+        Axis::Horizontal => BoxConstraints::new(
+            Size::new(min_major, bc.min().height),
+            Size::new(major, bc.max().height),
+        ),
+        Axis::Vertical => BoxConstraints::new(
+            Size::new(bc.min().width, min_major),
+            Size::new(bc.max().width, major),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::tests::harness::Harness;
+    use druid::widget::{
+        Align, Controller, EnvScope, Flex, Padding, Painter, Scroll, SizedBox,
+    };
+    use druid::{
+        lens, LifeCycleCtx, Modifiers, MouseButton, MouseButtons, MouseEvent,
+        PaintCtx, UpdateCtx, WidgetExt, WidgetId,
+    };
+    use std::cell::{Cell, RefCell};
+
+    /// Whether the pixel at `pos` of a painted `width` wide window is red.
+    fn is_red(pixels: &[u8], width: usize, pos: (usize, usize)) -> bool {
+        let start = (pos.1 * width + pos.0) * 4;
+        pixels[start..start + 4] == [255, 0, 0, 255]
+    }
+
+    #[test]
+    fn cell_clip_bounds_paint_to_the_cell() {
+        let paint_gap = |clip: bool| {
+            // children paint a shadow 10 past their bounds, into the gap
+            // between the first two cells at 20..40
+            let overpaint = || {
+                Painter::new(|ctx, _: &u32, _| {
+                    let shadow = ctx.size().to_rect().inflate(10., 10.);
+                    ctx.fill(shadow, &Color::RED);
+                })
+                .fix_size(20., 20.)
+            };
+            let grid = GridView::new(overpaint)
+                .with_spacing(20.)
+                .with_cell_clip(clip);
+            let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+            let data: Vector<u32> = (0..2).collect();
+            let mut gap = false;
+            Harness::create_with_render(
+                data,
+                grid,
+                Size::new(100., 40.),
+                |harness| {
+                    harness.send_initial_events();
+                    harness.paint();
+                },
+                |target| gap = is_red(&target.into_raw(), 100, (25, 10)),
+            );
+            gap
+        };
+        assert!(paint_gap(false));
+        assert!(!paint_gap(true));
+    }
+
+    fn grid() -> GridView<u32, Vector<u32>> {
+        GridView::new(SizedBox::empty)
+    }
+
+    /// A grid of 10x10 items, and the ids of the items in the order they are
+    /// built.
+    fn sized_grid<T: Data, D>() -> (GridView<T, D>, Rc<RefCell<Vec<WidgetId>>>)
+    {
+        let ids = Rc::new(RefCell::new(Vec::new()));
+        let built = ids.clone();
+        let grid = GridView::new(move || {
+            let id = WidgetId::next();
+            built.borrow_mut().push(id);
+            SizedBox::empty().fix_size(10., 10.).with_id(id)
+        });
+        (grid, ids)
+    }
+
+    /// The layout rects of the widgets with `ids`.
+    fn layout_rects<T: Data>(
+        harness: &mut Harness<T>,
+        ids: &RefCell<Vec<WidgetId>>,
+    ) -> Vec<Rect> {
+        let ids = ids.borrow().clone();
+        ids.iter()
+            .map(|id| harness.get_state(*id).layout_rect())
+            .collect()
+    }
+
+    #[test]
+    fn bounded_grid_takes_its_flex_share() {
+        let flex_share = BoxConstraints::new(Size::ZERO, Size::new(200., 300.));
+        let content = Size::new(200., 120.);
+        let size = grid_size(Axis::Vertical, &flex_share, content, |len| len);
+        assert_eq!(size, Size::new(200., 300.));
+
+        // the minor axis still shrinks to the content
+        let wide = BoxConstraints::new(Size::ZERO, Size::new(500., 300.));
+        let size = grid_size(Axis::Vertical, &wide, content, |len| len);
+        assert_eq!(size, Size::new(200., 300.));
+
+        let unbounded =
+            BoxConstraints::new(Size::ZERO, Size::new(200., f64::INFINITY));
+        let size = grid_size(Axis::Vertical, &unbounded, content, |len| len);
+        assert_eq!(size, content);
+    }
+
+    #[test]
+    fn bounded_grid_wraps_within_its_width() {
+        let mut grid = grid().wrap();
+        grid.cell_size = Size::new(50., 50.);
+        assert_eq!(grid.preferred_columns(200., &Env::empty()), 4);
+        assert_eq!(grid.preferred_columns(120., &Env::empty()), 2);
+    }
+
+    #[test]
+    fn env_spacing_follows_the_env() {
+        const SPACING: Key<f64> = Key::new("druid-gridview.test.spacing");
+        let grid = grid().with_spacing(SPACING);
+        let light = Env::empty().adding(SPACING, 4.);
+        let dark = Env::empty().adding(SPACING, 12.);
+        assert_eq!(grid.spacing(&light), (4., 4.));
+        assert_eq!(grid.spacing(&dark), (12., 12.));
+    }
+
+    /// A mouse event at `pos` while the left button is held, for `button`.
+    fn left_mouse(pos: Point, button: MouseButton) -> MouseEvent {
+        MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    #[derive(Clone, Data, Lens)]
+    struct Spaced {
+        spacing: f64,
+        items: Vector<u32>,
+    }
+
+    const SET_SPACING: Selector<f64> =
+        Selector::new("druid-gridview.test.set-spacing");
+
+    /// Sets the spacing in the data on [`SET_SPACING`].
+    struct SetSpacing;
+
+    impl<W: Widget<Spaced>> Controller<Spaced, W> for SetSpacing {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Spaced,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(SET_SPACING) => {
+                    data.spacing = *cmd.get_unchecked(SET_SPACING);
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    /// Passes everything on to its child, and records whether the child asked
+    /// for a layout the last time it was updated.
+    struct LayoutProbe<T, W> {
+        child: WidgetPod<T, W>,
+        requested: Rc<Cell<bool>>,
+    }
+
+    impl<T: Data, W: Widget<T>> Widget<T> for LayoutProbe<T, W> {
+        fn event(
+            &mut self,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut T,
+            env: &Env,
+        ) {
+            self.child.event(ctx, event, data, env);
+        }
+
+        fn lifecycle(
+            &mut self,
+            ctx: &mut LifeCycleCtx,
+            event: &LifeCycle,
+            data: &T,
+            env: &Env,
+        ) {
+            self.child.lifecycle(ctx, event, data, env);
+        }
+
+        fn update(&mut self, ctx: &mut UpdateCtx, _: &T, data: &T, env: &Env) {
+            self.child.update(ctx, data, env);
+            self.requested.set(self.child.layout_requested());
+        }
+
+        fn layout(
+            &mut self,
+            ctx: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            data: &T,
+            env: &Env,
+        ) -> Size {
+            let size = self.child.layout(ctx, bc, data, env);
+            self.child.set_origin(ctx, Point::ORIGIN);
+            size
+        }
+
+        fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+            self.child.paint(ctx, data, env);
+        }
+    }
+
+    #[test]
+    fn env_spacing_change_moves_the_items() {
+        const SPACING: Key<f64> = Key::new("druid-gridview.test.spacing");
+        let (grid, ids) = sized_grid();
+        let grid = grid.with_spacing(SPACING).lens(Spaced::items);
+        let requested = Rc::new(Cell::new(false));
+        let probe = LayoutProbe {
+            child: WidgetPod::new(grid),
+            requested: requested.clone(),
+        };
+        let themed = EnvScope::new(
+            |env, data: &Spaced| env.set(SPACING, data.spacing),
+            Align::new(UnitPoint::TOP_LEFT, probe),
+        )
+        .controller(SetSpacing);
+        let data = Spaced {
+            spacing: 4.,
+            items: (0..3).collect(),
+        };
+        Harness::create_simple(data, themed, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let xs = |harness: &mut Harness<Spaced>| {
+                let rects = layout_rects(harness, &ids);
+                rects.iter().map(|rect| rect.x0).collect::<Vec<_>>()
+            };
+            assert_eq!(xs(harness), [0., 14., 28.]);
+
+            harness.submit_command(SET_SPACING.with(12.));
+            assert!(requested.get());
+            harness.just_layout();
+            assert_eq!(xs(harness), [0., 22., 44.]);
+        });
+    }
+
+    #[test]
+    fn reorder_callback_follows_a_completed_drag() {
+        let completed = Rc::new(RefCell::new(Vec::new()));
+        let reported = completed.clone();
+        let (grid, _) = sized_grid();
+        let grid = grid.on_reorder_complete(move |_, from, to| {
+            reported.borrow_mut().push((from, to))
+        });
+        let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+        let data: Vector<u32> = (0..6).collect();
+        Harness::create_simple(data, grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // from the middle of item 1 to the middle of item 4
+            let (from, to) = (Point::new(15., 5.), Point::new(45., 5.));
+            harness
+                .event(Event::MouseDown(left_mouse(from, MouseButton::Left)));
+            harness.event(Event::MouseMove(left_mouse(to, MouseButton::None)));
+            assert!(completed.borrow().is_empty());
+            harness.event(Event::MouseUp(left_mouse(to, MouseButton::Left)));
+            assert_eq!(*completed.borrow(), [(1, 4)]);
+            let moved: Vec<u32> = harness.data().iter().copied().collect();
+            assert_eq!(moved, [0, 2, 3, 4, 1, 5]);
+        });
+    }
+
+    const KEEP_FIRST: Selector<usize> =
+        Selector::new("druid-gridview.test.keep-first");
+
+    /// Drops all but the given number of items on [`KEEP_FIRST`].
+    struct KeepFirst;
+
+    impl<W: Widget<Vector<u32>>> Controller<Vector<u32>, W> for KeepFirst {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Vector<u32>,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(KEEP_FIRST) => {
+                    data.truncate(*cmd.get_unchecked(KEEP_FIRST));
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    #[test]
+    fn removing_items_during_a_drag_cancels_it() {
+        let completed = Rc::new(RefCell::new(Vec::new()));
+        let reported = completed.clone();
+        let (grid, _) = sized_grid();
+        let grid = grid.with_drag_lift(Color::BLACK).on_reorder_complete(
+            move |_, from, to| reported.borrow_mut().push((from, to)),
+        );
+        let root = Align::new(UnitPoint::TOP_LEFT, grid).controller(KeepFirst);
+        let data: Vector<u32> = (0..6).collect();
+        Harness::create_with_render(
+            data,
+            root,
+            Size::new(50., 50.),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                // item 5 starts the second row, drag it onto item 1
+                let (from, to) = (Point::new(5., 15.), Point::new(15., 5.));
+                harness.event(Event::MouseDown(left_mouse(
+                    from,
+                    MouseButton::Left,
+                )));
+                harness
+                    .event(Event::MouseMove(left_mouse(to, MouseButton::None)));
+                harness.submit_command(KEEP_FIRST.with(3));
+                harness.just_layout();
+                harness.paint();
+                harness
+                    .event(Event::MouseUp(left_mouse(to, MouseButton::Left)));
+                let items: Vec<u32> = harness.data().iter().copied().collect();
+                assert_eq!(items, [0, 1, 2]);
+            },
+            |_| {},
+        );
+        assert!(completed.borrow().is_empty());
+    }
+
+    fn drag(source: usize, target: Option<usize>, active: bool) -> Drag {
+        Drag {
+            source,
+            start: Point::ZERO,
+            pos: Point::new(20., 20.),
+            active,
+            target,
+            cancelled: false,
+        }
+    }
+
+    #[test]
+    fn drop_target_is_the_item_under_the_drag() {
+        let mut grid = grid().with_drop_target_highlight(Color::BLACK);
+        grid.drag = Some(drag(0, Some(3), true));
+        assert_eq!(grid.drop_target(), Some(3));
+
+        // nothing is highlighted before the mouse moved far enough to drag
+        grid.drag = Some(drag(0, Some(3), false));
+        assert_eq!(grid.drop_target(), None);
+
+        grid.drag = Some(drag(3, Some(3), true));
+        assert_eq!(grid.drop_target(), None);
+    }
+
+    #[test]
+    fn initial_scroll_waits_for_its_item() {
+        let mut grid = grid().with_initial_scroll(2);
+        grid.update_child_count(&Vector::from(vec![1, 2]), &Env::empty());
+        grid.request_initial_scroll();
+        assert_eq!(grid.scroll_request, None);
+
+        grid.update_child_count(&Vector::from(vec![1, 2, 3]), &Env::empty());
+        grid.request_initial_scroll();
+        assert!(grid.scroll_request.is_some());
+        assert_eq!(grid.initial_scroll, None);
+
+        // the scroll happens only once
+        grid.scroll_request = None;
+        grid.request_initial_scroll();
+        assert_eq!(grid.scroll_request, None);
+    }
+
+    #[test]
+    fn headers_follow_the_sections() {
+        let data = Sectioned::new(vec![(1, 10), (1, 11), (2, 20)]);
+        assert_eq!(section_starts(&data), vec![(0, 1), (2, 2)]);
+
+        let mut grid = grid().with_section_header(|_| SizedBox::empty());
+        assert!(grid.update_headers(&data));
+        let ids = |grid: &GridView<u32, Vector<u32>>| {
+            grid.headers.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&grid), vec![1, 2]);
+        assert!(!grid.update_headers(&data));
+
+        let data = Sectioned::new(vec![(1, 10), (3, 30)]);
+        assert!(grid.update_headers(&data));
+        assert_eq!(ids(&grid), vec![1, 3]);
+    }
+
+    #[test]
+    fn hover_changes_repaint_the_items_involved() {
+        let data = Vector::from(vec![1, 2, 3]);
+        let mut hover = grid().with_repaint_on_hover(true);
+        hover.update_child_count(&data, &Env::empty());
+        assert_eq!(hover.set_hovered(Some(0)).len(), 1);
+        assert_eq!(hover.set_hovered(Some(1)).len(), 2);
+        assert!(hover.set_hovered(Some(1)).is_empty());
+        assert_eq!(hover.set_hovered(None).len(), 1);
+
+        let mut quiet = grid();
+        quiet.update_child_count(&data, &Env::empty());
+        assert!(quiet.set_hovered(Some(0)).is_empty());
+        assert_eq!(quiet.hovered, Some(0));
+    }
+
+    #[test]
+    fn snap_fits_whole_rows_with_spacing_and_margins() {
+        let mut cursor = Cursor::new(5., 10.);
+        cursor.line_major = 40.;
+        cursor.next_line();
+        // 2 rows: 2 * 40 + 10 + 2 * 5
+        assert_eq!(cursor.snap(100.), 100.);
+        assert_eq!(cursor.snap(149.), 100.);
+        assert_eq!(cursor.snap(150.), 150.);
+        // a single row is kept even if it doesn't fit
+        assert_eq!(cursor.snap(20.), 50.);
+
+        // nothing to snap to before the first row is complete
+        assert_eq!(Cursor::new(5., 10.).snap(123.), 123.);
+    }
+
+    #[test]
+    fn tracks_share_the_width_by_weight() {
+        let tracks = track_sizes(&[1., 2., 1.], 400., 0.);
+        assert_eq!(tracks, Some(vec![100., 200., 100.]));
+        let tracks = track_sizes(&[1., 2., 1.], 420., 10.);
+        assert_eq!(tracks, Some(vec![100., 200., 100.]));
+
+        assert_eq!(track_sizes(&[], 400., 0.), None);
+        assert_eq!(track_sizes(&[0., 0.], 400., 0.), None);
+        assert_eq!(track_sizes(&[1.], f64::INFINITY, 0.), None);
+    }
+
+    #[test]
+    fn marquee_selects_the_items_it_overlaps() {
+        // a grid scrolled down by 100, so the mouse is past the visible part
+        let bounds = Rect::new(0., 0., 100., 300.);
+        let start = clamp_point(Point::new(30., 130.), bounds);
+        let end = clamp_point(Point::new(150., 320.), bounds);
+        assert_eq!(end, Point::new(100., 300.));
+
+        let rects = (0..8).map(|idx| {
+            let origin =
+                Point::new((idx % 2) as f64 * 50., (idx / 2) as f64 * 50.);
+            Rect::from_origin_size(origin, (50., 50.))
+        });
+        let marquee = Rect::from_points(start, end);
+        let hits: Vec<_> = marquee_hits(marquee, rects.clone()).collect();
+        assert_eq!(hits, vec![4, 5, 6, 7]);
+
+        // the marquee only touches the row above it
+        let marquee = Rect::new(0., 100., 100., 120.);
+        let hits: Vec<_> = marquee_hits(marquee, rects).collect();
+        assert_eq!(hits, vec![4, 5]);
+    }
+
+    #[test]
+    fn arrow_keys_move_focus_along_a_row() {
+        assert!(!grid().wants_focus());
+        let mut focus = grid().with_selection_follows_focus(true);
+        assert!(focus.wants_focus());
+
+        focus.update_child_count(&Vector::from(vec![1, 2, 3]), &Env::empty());
+        // the first key press focuses the first item
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowRight), Some(0));
+        focus.focused = Some(1);
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowRight), Some(2));
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowLeft), Some(0));
+        focus.focused = Some(2);
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowRight), None);
+        assert_eq!(focus.focus_neighbor(&KbKey::Enter), None);
+    }
+
+    #[test]
+    fn preferred_columns_in_each_mode() {
+        let env = Env::empty();
+        let counted = grid().with_minor_axis_count(3);
+        assert_eq!(counted.preferred_columns(1000., &env), 3);
+        assert_eq!(counted.preferred_columns(10., &env), 3);
+
+        let mut wrapped = grid().wrap().with_spacing(10.);
+        // nothing is known about the items before the first layout
+        assert_eq!(wrapped.preferred_columns(170., &env), 1);
+        wrapped.cell_size = Size::new(50., 50.);
+        // the count only divides the space by the item size
+        assert_eq!(wrapped.preferred_columns(170., &env), 3);
+        assert_eq!(wrapped.preferred_columns(149., &env), 2);
+        assert_eq!(wrapped.preferred_columns(20., &env), 0);
+
+        let weighted = grid().wrap().with_weighted_columns(vec![1., 2., 1.]);
+        assert_eq!(weighted.preferred_columns(400., &env), 3);
+    }
+
+    #[test]
+    fn preferred_columns_match_a_layout() {
+        let mut measured = grid().wrap().with_spacing(5.);
+        measured.cell_size = Size::new(10., 10.);
+        for width in [25., 45., 50., 59., 60.] {
+            let (grid, ids) = sized_grid();
+            let grid = grid.wrap().with_spacing(5.);
+            let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+            let data: Vector<u32> = (0..20).collect();
+            let mut first_row = 0;
+            Harness::create_with_render(
+                data,
+                grid,
+                Size::new(width, 100.),
+                |harness| {
+                    harness.send_initial_events();
+                    harness.just_layout();
+                    let rects = layout_rects(harness, &ids);
+                    first_row = rects.iter().filter(|r| r.y0 == 0.).count();
+                },
+                |_| (),
+            );
+            let preferred = measured.preferred_columns(width, &Env::empty());
+            assert_eq!(preferred, first_row, "at {}", width);
+        }
+    }
+
+    #[test]
+    fn collapsed_margins_are_shared_between_neighbors() {
+        let env = Env::empty();
+        let separate = grid().with_cell_margin(5.);
+        assert_eq!(separate.spacing(&env), (10., 10.));
+        let collapsed =
+            grid().with_cell_margin(5.).with_cell_margin_collapse(true);
+        assert_eq!(collapsed.spacing(&env), (5., 5.));
+
+        // the margins at the edges of the grid aren't collapsed: 4 * 50 + 2 * 5
+        let mut collapsed = collapsed.wrap();
+        collapsed.cell_size = Size::new(50., 50.);
+        assert_eq!(collapsed.preferred_columns(210., &env), 4);
+        assert_eq!(collapsed.preferred_columns(209., &env), 3);
+
+        let spaced = collapsed.with_vertical_spacing(3.);
+        assert_eq!(spaced.spacing(&env), (8., 5.));
+    }
+
+    #[test]
+    fn tooltip_follows_the_hovered_item() {
+        let mut grid = grid().with_tooltip_delay(Duration::from_millis(500));
+        assert_eq!(grid.tooltip_hover(Some(1)), (false, Some(1)));
+        grid.tooltip_shown = Some(1);
+        // moving within the item keeps its tooltip up
+        assert_eq!(grid.tooltip_hover(Some(1)), (false, None));
+        assert_eq!(grid.tooltip_hover(Some(2)), (true, Some(2)));
+        assert_eq!(grid.tooltip_shown, None);
+        grid.tooltip_shown = Some(2);
+        assert_eq!(grid.tooltip_hover(None), (true, None));
+    }
+
+    #[test]
+    fn radial_items_are_spread_around_the_circle() {
+        let center = Point::new(100., 100.);
+        let points: Vec<_> = (0..4)
+            .map(|idx| radial_center(center, 50., 0., idx, 4))
+            .collect();
+        let expected = [(150., 100.), (100., 150.), (50., 100.), (100., 50.)];
+        for (point, (x, y)) in points.iter().zip(expected.iter()) {
+            assert!((point.x - x).abs() < 1e-9 && (point.y - y).abs() < 1e-9);
+        }
+
+        // the first item starts at the start angle
+        let top = radial_center(center, 50., -PI / 2., 0, 4);
+        assert!((top.x - 100.).abs() < 1e-9 && (top.y - 50.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chips_wrap_when_the_row_is_full() {
+        // a 100 wide grid with margins of 5 and 10 between the chips
+        let mut cursor = Cursor::new(5., 10.);
+        let minor_limit = 95.;
+        for _ in 0..2 {
+            assert!(cursor.fits(40., minor_limit));
+            cursor.minor += 40. + 10.;
+            cursor.column += 1;
+        }
+        assert!(!cursor.fits(40., minor_limit));
+
+        cursor.next_line();
+        assert_eq!(cursor.minor, 5.);
+        // a chip wider than the grid still gets a row of its own
+        assert!(cursor.fits(200., minor_limit));
+    }
+
+    #[test]
+    fn moved_items_keep_their_state() {
+        let moved: Vec<_> = (0..5).map(|idx| moved_index(idx, 1, 3)).collect();
+        assert_eq!(moved, vec![0, 3, 1, 2, 4]);
+        let moved: Vec<_> = (0..5).map(|idx| moved_index(idx, 3, 1)).collect();
+        assert_eq!(moved, vec![0, 2, 3, 1, 4]);
+
+        let mut data = Vector::from(vec![10, 11, 12, 13]);
+        let mut moving = grid();
+        moving.update_child_count(&data, &Env::empty());
+        moving.disabled = vec![false, true, false, false];
+        moving.focused = Some(1);
+        moving.hovered = Some(3);
+        assert!(data.move_item(1, 3));
+        moving.item_moved(1, 3);
+        assert_eq!(data, Vector::from(vec![10, 12, 13, 11]));
+        assert_eq!(moving.disabled, vec![false, false, false, true]);
+        assert_eq!(moving.focused, Some(3));
+        assert_eq!(moving.hovered, Some(2));
+    }
+
+    #[test]
+    fn paged_data_is_not_reordered() {
+        let mut data = Paged::<u32>::new(4, 2);
+        data.set_page(0, vec![1, 2]);
+        assert!(!data.move_item(0, 1));
+        assert!(!Vector::from(vec![1, 2]).move_item(0, 2));
+    }
+
+    /// Items that can be disabled individually and have a kind.
+    #[derive(Clone, Data)]
+    struct Items {
+        items: Vector<u32>,
+        disabled: Vector<usize>,
+        kinds: Vector<u32>,
+    }
+
+    impl Items {
+        fn new(len: u32) -> Self {
+            Items {
+                items: (0..len).collect(),
+                disabled: Vector::new(),
+                kinds: Vector::new(),
+            }
+        }
+    }
+
+    impl GridIter<u32> for Items {
+        fn for_each(&self, mut cb: impl FnMut(&u32, usize)) {
+            for (idx, item) in self.items.iter().enumerate() {
+                cb(item, idx);
+            }
+        }
+
+        fn for_each_mut(&mut self, mut cb: impl FnMut(&mut u32, usize)) {
+            for (idx, item) in self.items.iter_mut().enumerate() {
+                cb(item, idx);
+            }
+        }
+
+        fn data_len(&self) -> usize {
+            self.items.len()
+        }
+
+        fn child_data(&self) -> Option<u32> {
+            self.items.front().copied()
+        }
+
+        fn is_disabled(&self, index: usize) -> bool {
+            self.disabled.contains(&index)
+        }
+
+        fn kind(&self, index: usize) -> u32 {
+            self.kinds.get(index).copied().unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn disabled_items_are_skipped() {
+        let mut data = Items::new(4);
+        data.disabled = Vector::from(vec![2]);
+        let mut focus = grid().with_selection_follows_focus(true);
+        focus.update_child_count(&data, &Env::empty());
+        focus.update_disabled(&data);
+        assert!(focus.is_disabled(2));
+        assert!(!focus.is_disabled(1) && !focus.is_disabled(3));
+
+        focus.focused = Some(1);
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowRight), Some(3));
+        focus.focused = Some(3);
+        assert_eq!(focus.focus_neighbor(&KbKey::ArrowLeft), Some(1));
+
+        data.disabled.clear();
+        focus.update_disabled(&data);
+        assert!(!focus.is_disabled(2));
+    }
+
+    /// Lay out, then deliver the commands sent during layout, like the work the
+    /// grid defers to an event.
+    fn layout_and_run<T: Data>(harness: &mut Harness<T>) {
+        harness.just_layout();
+        harness.submit_command(Selector::NOOP);
+    }
+
+    #[test]
+    fn scroll_offset_is_saved_and_restored() {
+        type Saved = (Vec2, Vector<u32>);
+        let scrolled = |grid: GridView<(Vec2, u32), Saved>, id| {
+            let grid = grid.with_scroll_offset_sink(lens!(Saved, 0));
+            Scroll::new(grid.with_id(id)).vertical()
+        };
+        // 20 rows of 10 in a window 50 high
+        let items: Vector<u32> = (0..100).collect();
+        let size = Size::new(50., 50.);
+        let id = WidgetId::next();
+
+        let (grid, _) = sized_grid();
+        let saving = scrolled(grid, id);
+        let data = (Vec2::ZERO, items.clone());
+        let save = |harness: &mut Harness<Saved>| {
+            harness.send_initial_events();
+            layout_and_run(harness);
+            // nothing is written before the grid was scrolled
+            assert_eq!(harness.data().0, Vec2::ZERO);
+            let mut wheel = left_mouse(Point::new(25., 25.), MouseButton::None);
+            wheel.wheel_delta = Vec2::new(0., 60.);
+            harness.event(Event::Wheel(wheel));
+            assert_eq!(harness.data().0, Vec2::new(0., 60.));
+        };
+        Harness::create_with_render(data, saving, size, save, |_| ());
+
+        let (grid, _) = sized_grid();
+        let restoring =
+            scrolled(grid.with_scroll_offset(Vec2::new(0., 120.)), id);
+        let data = (Vec2::ZERO, items);
+        let restore = |harness: &mut Harness<Saved>| {
+            harness.send_initial_events();
+            layout_and_run(harness);
+            let origin = harness.get_state(id).layout_rect().origin();
+            assert_eq!(origin, Point::new(0., -120.));
+            assert_eq!(harness.data().0, Vec2::new(0., 120.));
+
+            harness.submit_command(
+                SET_SCROLL_OFFSET.with(Vec2::new(0., 30.)).to(id),
+            );
+            let origin = harness.get_state(id).layout_rect().origin();
+            assert_eq!(origin, Point::new(0., -30.));
+            assert_eq!(harness.data().0, Vec2::new(0., 30.));
+        };
+        Harness::create_with_render(data, restoring, size, restore, |_| ());
+    }
+
+    #[test]
+    fn layout_fn_places_the_items() {
+        // a diagonal, one item below and to the right of the one before
+        let (grid, ids) = sized_grid();
+        let diagonal = grid.with_layout_fn(|idx, _, size| {
+            Point::new(idx as f64 * size.width, idx as f64 * size.height)
+        });
+        let diagonal = Align::new(UnitPoint::TOP_LEFT, diagonal);
+        let data: Vector<u32> = (0..3).collect();
+        Harness::create_simple(data, diagonal, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rects = layout_rects(harness, &ids);
+            assert_eq!(
+                rects,
+                [
+                    Rect::new(0., 0., 10., 10.),
+                    Rect::new(10., 10., 20., 20.),
+                    Rect::new(20., 20., 30., 30.),
+                ]
+            );
+        });
+    }
+
+    /// The item clicked at 7, 5 in a row of 10x10 items that each overlap
+    /// half of the one before, with the items' z order set up by `configure`.
+    fn clicked_overlapping_item(
+        configure: impl FnOnce(
+            GridView<u32, Vector<u32>>,
+        ) -> GridView<u32, Vector<u32>>,
+    ) -> Vec<usize> {
+        let clicked = Rc::new(RefCell::new(Vec::new()));
+        let reported = clicked.clone();
+        let (grid, _) = sized_grid();
+        let grid = grid
+            .with_layout_fn(|idx, _, _| Point::new(idx as f64 * 5., 0.))
+            .on_item_click(move |_, _, idx| reported.borrow_mut().push(idx));
+        let grid = Align::new(UnitPoint::TOP_LEFT, configure(grid));
+        let data: Vector<u32> = (0..3).collect();
+        Harness::create_simple(data, grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let pos = Point::new(7., 5.);
+            harness.event(Event::MouseDown(left_mouse(pos, MouseButton::Left)));
+            harness.event(Event::MouseUp(left_mouse(pos, MouseButton::Left)));
+        });
+        let clicked = clicked.borrow().clone();
+        clicked
+    }
+
+    #[test]
+    fn clicks_go_to_the_item_painted_on_top() {
+        // later items are painted over earlier ones
+        assert_eq!(clicked_overlapping_item(|grid| grid), [1]);
+        let raised = |grid: GridView<_, _>| {
+            grid.with_cell_z_order(|idx| (idx == 0) as i32)
+        };
+        assert_eq!(clicked_overlapping_item(raised), [0]);
+    }
+
+    #[test]
+    fn min_wrap_gap_drops_a_column() {
+        let env = Env::empty();
+        let mut loose = grid().wrap().with_min_gap_on_wrap(10.);
+        loose.cell_size = Size::new(50., 50.);
+        // 4 items leave exactly the minimum gap
+        assert_eq!(loose.preferred_columns(210., &env), 4);
+
+        let mut tight = grid().wrap().with_min_gap_on_wrap(20.);
+        tight.cell_size = Size::new(50., 50.);
+        assert_eq!(tight.preferred_columns(210., &env), 3);
+        // the last item is never dropped
+        assert_eq!(tight.preferred_columns(60., &env), 1);
+    }
+
+    const SCROLL_ITSELF: Selector =
+        Selector::new("druid-gridview.test.scroll-itself");
+
+    /// Scrolls the widget it wraps into view on [`SCROLL_ITSELF`].
+    struct ScrollsItself;
+
+    impl<T, W: Widget<T>> Controller<T, W> for ScrollsItself {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut T,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(SCROLL_ITSELF) => {
+                    ctx.scroll_to_view();
+                    ctx.set_handled();
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    /// How far a 50x50 `Scroll` has scrolled after a 6x6 box inside item 47
+    /// of a grid of 10x10 items, set up by `configure`, scrolls itself into
+    /// view.
+    fn nested_scroll_offset(
+        configure: impl FnOnce(
+            GridView<u32, Vector<u32>>,
+        ) -> GridView<u32, Vector<u32>>,
+    ) -> f64 {
+        let inner = Rc::new(RefCell::new(Vec::new()));
+        let ids = inner.clone();
+        let grid = GridView::new(move || {
+            let id = WidgetId::next();
+            ids.borrow_mut().push(id);
+            let inner = SizedBox::empty().fix_size(6., 6.);
+            Padding::new(2., inner.controller(ScrollsItself).with_id(id))
+        });
+        let id = WidgetId::next();
+        let root = Scroll::new(configure(grid).with_id(id)).vertical();
+        // 20 rows of 5
+        let data = (0..100).collect::<Vector<u32>>();
+        let mut offset = 0.;
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            harness.just_layout();
+            let target = inner.borrow()[47];
+            harness.submit_command(SCROLL_ITSELF.to(target));
+            harness.just_layout();
+            offset = -harness.get_state(id).layout_rect().y0;
+        });
+        offset
+    }
+
+    #[test]
+    fn nested_scroll_requests_reach_the_scroll() {
+        // the box is at 92..98, so its bottom lines up with the bottom
+        assert_eq!(nested_scroll_offset(|grid| grid), 48.);
+    }
+
+    #[test]
+    fn nested_scroll_requests_follow_the_painted_item() {
+        // an item that hasn't started to slide in is painted 10 lower
+        let sliding =
+            |grid: GridView<_, _>| grid.with_entrance(Entrance::FromBottom);
+        assert_eq!(nested_scroll_offset(sliding), 58.);
+    }
+
+    #[test]
+    fn z_order_paints_raised_items_last() {
+        let data = Vector::from(vec![1, 2, 3, 4]);
+        let mut flat = grid();
+        flat.update_child_count(&data, &Env::empty());
+        assert_eq!(flat.paint_order(), vec![0, 1, 2, 3]);
+
+        let mut raised = grid().with_cell_z_order(|idx| (idx == 2) as i32);
+        raised.update_child_count(&data, &Env::empty());
+        assert_eq!(raised.paint_order(), vec![0, 1, 3, 2]);
+
+        // items with the same z keep their data order
+        let mut sunk = grid().with_cell_z_order(|idx| -((idx % 2) as i32));
+        sunk.update_child_count(&data, &Env::empty());
+        assert_eq!(sunk.paint_order(), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn aggregate_row_sticks_to_the_bottom_while_scrolling() {
+        // 20 rows of 5 in a window 50 high, the row is red
+        let row_id = WidgetId::next();
+        let bottom_is_red = |scroll: f64| {
+            let (grid, _) = sized_grid();
+            let grid = grid.with_aggregate_row(move |_: &Vector<u32>| {
+                Painter::new(|ctx, _: &Vector<u32>, _| {
+                    let rect = ctx.size().to_rect();
+                    ctx.fill(rect, &Color::RED);
+                })
+                .fix_height(10.)
+                .with_id(row_id)
+                .boxed()
+            });
+            let root = Scroll::new(grid).vertical();
+            let data: Vector<u32> = (0..100).collect();
+            let mut red = false;
+            Harness::create_with_render(
+                data,
+                root,
+                Size::new(50., 50.),
+                |harness| {
+                    harness.send_initial_events();
+                    layout_and_run(harness);
+                    // laid out after the last row
+                    let row = harness.get_state(row_id).layout_rect();
+                    assert_eq!(row, Rect::new(0., 200., 50., 210.));
+                    let pos = Point::new(25., 25.);
+                    let mut wheel = left_mouse(pos, MouseButton::None);
+                    wheel.wheel_delta = Vec2::new(0., scroll);
+                    harness.event(Event::Wheel(wheel));
+                    harness.paint();
+                },
+                |target| red = is_red(&target.into_raw(), 50, (25, 45)),
+            );
+            red
+        };
+        assert!(bottom_is_red(0.));
+        assert!(bottom_is_red(60.));
+    }
+
+    #[test]
+    fn aggregate_row_edits_the_data() {
+        let (grid, _) = sized_grid();
+        let grid = grid.with_aggregate_row(|data: &Vector<u32>| {
+            // the row is built from the data
+            assert_eq!(data.len(), 6);
+            SizedBox::empty()
+                .expand_width()
+                .height(10.)
+                .on_click(|_, data: &mut Vector<u32>, _| data.push_back(99))
+                .boxed()
+        });
+        let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+        let data: Vector<u32> = (0..6).collect();
+        Harness::create_with_render(
+            data,
+            grid,
+            Size::new(50., 50.),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                // 2 rows of 5, so the aggregate row is at 20..30
+                let pos = Point::new(5., 25.);
+                harness.event(Event::MouseDown(left_mouse(
+                    pos,
+                    MouseButton::Left,
+                )));
+                harness
+                    .event(Event::MouseUp(left_mouse(pos, MouseButton::Left)));
+                let items: Vec<u32> = harness.data().iter().copied().collect();
+                assert_eq!(items, [0, 1, 2, 3, 4, 5, 99]);
+            },
+            |_| {},
+        );
+    }
+
+    #[test]
+    fn cache_reuses_the_widgets_of_removed_items() {
+        let built = Rc::new(std::cell::Cell::new(0));
+        let counter = built.clone();
+        let mut cached: GridView<u32, Vector<u32>> = GridView::new(move || {
+            counter.set(counter.get() + 1);
+            SizedBox::<u32>::empty()
+        })
+        .with_cell_builder_cache(2);
+        let env = Env::empty();
+        cached.update_child_count(&Vector::from(vec![1, 2, 3, 4]), &env);
+        assert_eq!(built.get(), 4);
+
+        cached.update_child_count(&Vector::from(vec![1]), &env);
+        assert_eq!(cached.cache.len(), 2);
+        cached.update_child_count(&Vector::from(vec![1, 2, 3, 4]), &env);
+        // two widgets came from the cache, only one had to be built
+        assert_eq!(built.get(), 5);
+        assert!(cached.cache.is_empty());
+    }
+
+    #[test]
+    fn ordered_selection_keeps_the_pick_order() {
+        type Picked = (OrdSet<usize>, Vector<u32>);
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let order = reported.clone();
+        let (grid, _) = sized_grid::<(OrdSet<usize>, u32), Picked>();
+        let grid = grid
+            .with_ordered_selection(lens!(Picked, 0))
+            .on_selection_change(move |_, picked| {
+                *order.borrow_mut() = picked.to_vec()
+            });
+        let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+        let data = (OrdSet::new(), (0..6).collect());
+        Harness::create_simple(data, grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            for idx in [3, 1, 2] {
+                let pos = Point::new(idx as f64 * 10. + 5., 5.);
+                let click = left_mouse(pos, MouseButton::Left);
+                harness.event(Event::MouseDown(click.clone()));
+                harness.event(Event::MouseUp(click));
+            }
+            let picked: Vec<usize> = harness.data().0.iter().copied().collect();
+            assert_eq!(picked, [1, 2, 3]);
+        });
+        assert_eq!(*reported.borrow(), [3, 1, 2]);
+    }
+
+    #[test]
+    fn picks_from_the_data_go_after_the_picked_items() {
+        let picked = OrdSet::from(vec![0, 2, 3]);
+        assert_eq!(in_pick_order(&[3, 1, 2], &picked), [3, 2, 0]);
+        assert_eq!(in_pick_order(&[], &picked), [0, 2, 3]);
+    }
+
+    #[test]
+    fn edge_fade_covers_items_clipped_at_the_edge() {
+        let mut faded = grid().with_edge_fade(20.);
+        faded.viewport = Rect::new(0., 100., 200., 300.);
+        let size = Size::new(200., 1000.);
+        let bands: Vec<_> = faded
+            .overflow_bands(size, faded.edge_fade)
+            .into_iter()
+            .map(|(band, _, _)| band)
+            .collect();
+        assert_eq!(
+            bands,
+            vec![
+                Rect::new(0., 100., 200., 120.),
+                Rect::new(0., 280., 200., 300.)
+            ]
+        );
+        // an item cut off by the top edge is painted over
+        let clipped = Rect::new(0., 90., 50., 140.);
+        assert!(bands[0].intersect(clipped).area() > 0.);
+        assert_eq!(faded.overflow_bands(size, 0.).len(), 0);
+    }
+
+    #[test]
+    fn dragging_a_border_resizes_its_columns() {
+        let env = Env::empty();
+        let mut table = grid()
+            .with_spacing(0.)
+            .with_cell_margin(0.)
+            .with_weighted_columns(vec![1., 1., 1.]);
+        table.tracks = vec![100., 100., 100.];
+        assert_eq!(table.column_border_at(Point::new(102., 5.), &env), Some(0));
+        assert_eq!(table.column_border_at(Point::new(199., 5.), &env), Some(1));
+        assert_eq!(table.column_border_at(Point::new(150., 5.), &env), None);
+        // the end of the last column isn't a border between columns
+        assert_eq!(table.column_border_at(Point::new(300., 5.), &env), None);
+
+        table.move_border(0, 100., 130.);
+        assert_eq!(table.tracks, vec![130., 70., 100.]);
+        assert_eq!(table.column_weights, Some(table.tracks.clone()));
+        assert_eq!(table.resizing, Some((0, 130.)));
+
+        // the column before the border can't shrink below nothing
+        table.move_border(0, 130., -500.);
+        assert_eq!(table.tracks, vec![0., 200., 100.]);
+        assert_eq!(table.resizing, Some((0, 0.)));
+    }
+
+    #[test]
+    fn reduced_motion_skips_the_entrance() {
+        let reduced = Env::empty().adding(REDUCED_MOTION, true);
+        let data = Vector::from(vec![1, 2, 3]);
+
+        let mut calm = grid()
+            .with_stagger(Duration::from_millis(50))
+            .with_respect_reduced_motion(true);
+        assert!(calm.reduces_motion(&reduced));
+        assert!(!calm.reduces_motion(&Env::empty()));
+        calm.update_child_count(&data, &reduced);
+        assert!(calm.appear.is_empty());
+        assert!(!calm.is_appearing());
+
+        // the setting is ignored unless the grid respects it
+        let mut lively = grid().with_stagger(Duration::from_millis(50));
+        assert!(!lively.reduces_motion(&reduced));
+        lively.update_child_count(&data, &reduced);
+        assert!(lively.is_appearing());
+    }
+
+    #[test]
+    fn points_along_a_path_follow_its_segments() {
+        let mut path = BezPath::new();
+        path.move_to((0., 0.));
+        path.line_to((100., 0.));
+        path.line_to((100., 50.));
+        let segments: Vec<_> = path
+            .segments()
+            .map(|seg| (seg, seg.arclen(ARCLEN_ACCURACY)))
+            .collect();
+        let close = |a: Point, b: Point| (a - b).hypot() < 1e-6;
+        assert!(close(point_along(&segments, 0.), Point::ZERO));
+        assert!(close(point_along(&segments, 50.), Point::new(50., 0.)));
+        assert!(close(point_along(&segments, 125.), Point::new(100., 25.)));
+        // rounding past the end lands on the end
+        assert!(close(point_along(&segments, 150.1), Point::new(100., 50.)));
+        assert_eq!(point_along(&[], 10.), Point::ZERO);
+    }
+
+    #[test]
+    fn only_items_out_of_the_default_state_get_their_own_env() {
+        const STATE: Key<u64> = Key::new("druid-gridview.test.state");
+        let env = Env::empty();
+        let mut stateful = grid().with_cell_state_env(STATE);
+        stateful.update_child_count(&Vector::from(vec![1, 2, 3, 4]), &env);
+        stateful.selection = vec![1];
+        stateful.hovered = Some(1);
+        stateful.focused = Some(2);
+        let envs = stateful.cell_envs(&env);
+        assert_eq!(envs.envs.len(), 2);
+        let state = |idx| CellState::from_env(envs.get(idx, &env), &STATE);
+        let selected_and_hovered = CellState {
+            selected: true,
+            hovered: true,
+            ..CellState::default()
+        };
+        assert_eq!(state(1), selected_and_hovered);
+        assert!(state(2).focused);
+        assert_eq!(state(0), CellState::default());
+        assert_eq!(envs.get(0, &env).try_get(STATE).ok(), Some(0));
+
+        // the envs are kept until a state changes
+        assert!(Rc::ptr_eq(&envs, &stateful.cell_envs(&env)));
+        stateful.hovered = Some(3);
+        assert!(!Rc::ptr_eq(&envs, &stateful.cell_envs(&env)));
+
+        let plain = grid().cell_envs(&env);
+        assert!(plain.default.is_none() && plain.envs.is_empty());
+    }
+
+    #[test]
+    fn selected_and_hovered_item_gets_both_states() {
+        const STATE: Key<u64> = Key::new("druid-gridview.test.state");
+        type Picked = (OrdSet<usize>, Vector<u32>);
+        let painted = Rc::new(RefCell::new(HashMap::new()));
+        let seen = painted.clone();
+        let item = move || {
+            let seen = seen.clone();
+            Painter::new(move |_, (_, item): &(OrdSet<usize>, u32), env| {
+                let state = CellState::from_env(env, &STATE);
+                seen.borrow_mut().insert(*item, state);
+            })
+            .fix_size(10., 10.)
+        };
+        let grid = GridView::new(item)
+            .with_cell_state_env(STATE)
+            .with_ordered_selection(lens!(Picked, 0));
+        let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+        let data = (OrdSet::new(), (0..3).collect());
+        Harness::create_simple(data, grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // pick item 1 and leave the mouse over it
+            let pos = Point::new(15., 5.);
+            let click = left_mouse(pos, MouseButton::Left);
+            harness.event(Event::MouseDown(click.clone()));
+            harness.event(Event::MouseUp(click));
+            harness.event(Event::MouseMove(left_mouse(pos, MouseButton::None)));
+            harness.paint();
+        });
+        let painted = painted.borrow();
+        let selected_and_hovered = CellState {
+            selected: true,
+            hovered: true,
+            ..CellState::default()
+        };
+        assert_eq!(painted[&1], selected_and_hovered);
+        assert_eq!(painted[&0], CellState::default());
+    }
+
+    #[test]
+    fn cell_state_survives_the_env() {
+        for bits in 0..16 {
+            assert_eq!(CellState::from_bits(bits).to_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn paged_items_are_pending_until_their_page_loads() {
+        let mut paged = Paged::new(5, 2);
+        assert_eq!(paged.data_len(), 5);
+        assert_eq!(paged.page_of(3), 1);
+        assert!(paged.is_pending(3));
+        assert!(!paged.is_pending(5));
+
+        paged.set_page(1, vec![12_u32, 13]);
+        let mut items = Vec::new();
+        paged.for_each(|item, _| items.push(*item));
+        assert_eq!(items, vec![None, None, Some(12), Some(13), None]);
+
+        // only loaded items can be changed
+        paged.for_each_mut(|item, _| *item = Some(0));
+        let mut items = Vec::new();
+        paged.for_each(|item, _| items.push(*item));
+        assert_eq!(items, vec![None, None, Some(0), Some(0), None]);
+
+        let mut placeholders: GridView<Option<u32>, Paged<u32>> =
+            GridView::new(SizedBox::empty);
+        placeholders.update_child_count(&paged, &Env::empty());
+        assert_eq!(placeholders.children.len(), 5);
+    }
+
+    #[test]
+    fn shared_data_reaches_every_item_and_back() {
+        let mut zoomed = (2., Vector::from(vec![10_u32, 20, 30]));
+        let mut items = Vec::new();
+        zoomed.for_each(|item, _| items.push(*item));
+        assert_eq!(items, vec![(2., 10), (2., 20), (2., 30)]);
+        assert_eq!(zoomed.data_len(), 3);
+        assert_eq!(zoomed.child_data(), Some((2., 10)));
+
+        // an item changing the shared data changes it for all of them
+        zoomed.for_each_mut(|(zoom, item), idx| {
+            if idx == 1 {
+                *zoom = 3.;
+                *item += 1;
+            }
+        });
+        assert_eq!(zoomed, (3., Vector::from(vec![10, 21, 30])));
+        assert!(zoomed.move_item(0, 2));
+        assert_eq!(zoomed.1, Vector::from(vec![21, 30, 10]));
+    }
+
+    #[test]
+    fn trailing_anchor_moves_short_rows_to_the_end() {
+        // a row of two 100 wide items in a 300 wide grid with margins of 5
+        let trailing_edge = 300. - 5.;
+        let line_end = 5. + 100. + 10. + 100.;
+        assert_eq!(
+            trailing_shift(Axis::Vertical, trailing_edge, line_end),
+            Vec2::new(80., 0.)
+        );
+        assert_eq!(
+            trailing_shift(Axis::Horizontal, trailing_edge, line_end),
+            Vec2::new(0., 80.)
+        );
+        // a full row stays where it is
+        assert_eq!(
+            trailing_shift(Axis::Vertical, trailing_edge, trailing_edge),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn ratio_range_limits_the_item_height() {
+        let range = grid().with_cell_ratio_range(1., 2.).ratio_range.unwrap();
+        // a 100 wide item can be 50 to 100 tall
+        let tall =
+            ratio_constraints(Axis::Vertical, range, Size::new(100., 300.));
+        let tall = tall.unwrap();
+        assert_eq!(tall.min(), Size::new(100., 50.));
+        assert_eq!(tall.max(), Size::new(100., 100.));
+        let fine = Size::new(100., 80.);
+        assert!(ratio_constraints(Axis::Vertical, range, fine).is_none());
+        // a wider column allows taller items
+        let wide = Size::new(200., 150.);
+        assert!(ratio_constraints(Axis::Vertical, range, wide).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "greater than zero")]
+    fn ratio_range_has_to_be_positive() {
+        grid().with_cell_ratio_range(0., 2.);
+    }
+
+    #[test]
+    fn enter_and_space_activate_items() {
+        assert!(is_activation_key(&KbKey::Enter));
+        assert!(is_activation_key(&KbKey::Character(" ".into())));
+        assert!(!is_activation_key(&KbKey::Character("a".into())));
+        assert!(!is_activation_key(&KbKey::ArrowDown));
+    }
+
+    fn key_down(key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key))
+    }
+
+    #[test]
+    fn focus_stays_on_items_that_are_laid_out() {
+        let clicked = Rc::new(RefCell::new(Vec::new()));
+        let reported = clicked.clone();
+        let (grid, _) = sized_grid();
+        let grid = grid
+            .with_incremental_layout(3)
+            .on_item_click(move |_, _, idx| reported.borrow_mut().push(idx));
+        let grid = Align::new(UnitPoint::TOP_LEFT, grid);
+        let data: Vector<u32> = (0..6).collect();
+        Harness::create_simple(data, grid, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            // the first pass lays out items 0 to 2
+            harness.just_layout();
+            let pos = Point::new(25., 5.);
+            harness.event(Event::MouseDown(left_mouse(pos, MouseButton::Left)));
+            harness.event(Event::MouseUp(left_mouse(pos, MouseButton::Left)));
+            harness.event(key_down(KbKey::ArrowRight));
+            harness.event(key_down(KbKey::Enter));
+        });
+        assert_eq!(*clicked.borrow(), [2, 2]);
+    }
+
+    #[test]
+    fn initial_scroll_waits_for_incremental_layout() {
+        let (grid, _) = sized_grid();
+        let grid = grid.with_incremental_layout(10).with_initial_scroll(47);
+        let id = WidgetId::next();
+        let root = Scroll::new(grid.with_id(id)).vertical();
+        // 20 rows of 5 in a window 50 high
+        let data: Vector<u32> = (0..100).collect();
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            for _ in 0..10 {
+                layout_and_run(harness);
+            }
+            harness.just_layout();
+            // the row of item 47 is at 90..100
+            assert_eq!(harness.get_state(id).layout_rect().y0, -50.);
+        });
+    }
+
+    const GROW_FIRST: Selector =
+        Selector::new("druid-gridview.test.grow-first");
+
+    /// Makes the first item 20 high on [`GROW_FIRST`].
+    struct GrowFirst;
+
+    impl<W: Widget<Vector<u32>>> Controller<Vector<u32>, W> for GrowFirst {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Vector<u32>,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(GROW_FIRST) => {
+                    data.set(0, 20);
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    /// A 10 wide item as high as its data.
+    struct Tall;
+
+    impl Widget<u32> for Tall {
+        fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut u32, _: &Env) {
+        }
+
+        fn lifecycle(
+            &mut self,
+            _: &mut LifeCycleCtx,
+            _: &LifeCycle,
+            _: &u32,
+            _: &Env,
+        ) {
+        }
+
+        fn update(
+            &mut self,
+            ctx: &mut UpdateCtx,
+            old: &u32,
+            data: &u32,
+            _: &Env,
+        ) {
+            if old != data {
+                ctx.request_layout();
+            }
+        }
+
+        fn layout(
+            &mut self,
+            _: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            data: &u32,
+            _: &Env,
+        ) -> Size {
+            bc.constrain(Size::new(10., *data as f64))
+        }
+
+        fn paint(&mut self, _: &mut PaintCtx, _: &u32, _: &Env) {}
+    }
+
+    #[test]
+    fn anchored_items_are_laid_out_when_they_ask() {
+        let ids = Rc::new(RefCell::new(Vec::new()));
+        let built = ids.clone();
+        let grid = GridView::new(move || {
+            let id = WidgetId::next();
+            built.borrow_mut().push(id);
+            Tall.with_id(id)
+        })
+        .with_incremental_layout(2);
+        let root = Align::new(UnitPoint::TOP_LEFT, grid).controller(GrowFirst);
+        let data = Vector::from(vec![10, 10, 10, 10]);
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            // the next pass keeps the first two items where they are
+            layout_and_run(harness);
+            harness.submit_command(GROW_FIRST);
+            harness.just_layout();
+            let first = harness.get_state(ids.borrow()[0]).layout_rect();
+            assert_eq!(first.height(), 20.);
+        });
+    }
+
+    #[test]
+    fn incremental_layout_adds_a_budget_of_items_per_pass() {
+        assert_eq!(grid().layout_end(), usize::MAX);
+
+        let mut budgeted = grid().with_incremental_layout(50);
+        assert_eq!(budgeted.layout_end(), 50);
+        budgeted.laid_out = 50;
+        assert_eq!(budgeted.layout_end(), 100);
+        // shrinking data forgets the items that are gone
+        budgeted
+            .update_child_count(&Vector::from(vec![1, 2, 3]), &Env::empty());
+        assert_eq!(budgeted.laid_out, 3);
+        assert_eq!(grid().with_incremental_layout(0).layout_end(), 1);
+    }
+
+    #[test]
+    fn lifted_item_follows_the_mouse() {
+        let data = Vector::from(vec![1, 2, 3]);
+        let mut lifting = grid().with_drag_lift(Color::BLACK);
+        lifting.update_child_count(&data, &Env::empty());
+        lifting.drag = Some(drag(1, None, true));
+        assert_eq!(
+            lifting.lift_offsets(),
+            vec![Vec2::ZERO, Vec2::new(20., 20.), Vec2::ZERO]
+        );
+
+        // nothing is lifted before the drag starts
+        lifting.drag = Some(drag(1, None, false));
+        assert!(lifting.lift_offsets().is_empty());
+
+        let mut flat = grid().with_drop_target_highlight(Color::BLACK);
+        flat.update_child_count(&data, &Env::empty());
+        flat.drag = Some(drag(1, None, true));
+        assert!(flat.lift_offsets().is_empty());
+    }
+
+    #[test]
+    fn selection_follows_focus_only_when_enabled() {
+        let data = Vector::from(vec![1, 2, 3]);
+        let mut following = grid().with_selection_follows_focus(true);
+        following.update_child_count(&data, &Env::empty());
+        let next = following.focus_neighbor(&KbKey::ArrowRight).unwrap();
+        assert_eq!(following.focus(next), Some(vec![0]));
+        let next = following.focus_neighbor(&KbKey::ArrowRight).unwrap();
+        assert_eq!(following.focus(next), Some(vec![1]));
+        assert_eq!(following.focused, Some(1));
+
+        let mut independent = grid()
+            .with_selection_follows_focus(false)
+            .on_cell_key(|_, _, _, _| ());
+        independent.update_child_count(&data, &Env::empty());
+        let next = independent.focus_neighbor(&KbKey::ArrowRight).unwrap();
+        assert_eq!(independent.focus(next), None);
+        assert_eq!(independent.focused, Some(0));
+    }
+
+    #[test]
+    fn stripes_keep_their_parity_while_scrolling() {
+        let mut striped = grid().with_row_stripes(Color::BLACK);
+        striped.rows = (0..10).map(|row| (row as f64 * 50., 40.)).collect();
+        let stripe = |row: f64| Rect::new(0., row * 50., 200., row * 50. + 40.);
+
+        let top = Rect::new(0., 0., 200., 100.);
+        assert_eq!(striped.stripe_rects(200., top), vec![stripe(1.)]);
+        // scrolled so the painted part starts at row 3
+        let scrolled = Rect::new(0., 150., 200., 300.);
+        assert_eq!(
+            striped.stripe_rects(200., scrolled),
+            vec![stripe(3.), stripe(5.)]
+        );
+    }
+
+    #[test]
+    fn stagger_starts_each_item_after_the_one_before() {
+        let env = Env::empty();
+        let mut staggered = grid().with_stagger(Duration::from_millis(100));
+        staggered.update_child_count(&Vector::from(vec![1, 2, 3]), &env);
+        let expected = [0., -0.1, -0.2];
+        let close = |appear: &[f64], expected: &[f64]| {
+            appear.len() == expected.len()
+                && appear
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, b)| (a - b).abs() < 1e-9)
+        };
+        assert!(close(&staggered.appear, &expected));
+
+        // items added later start their own cascade, the others keep going
+        staggered.appear = vec![APPEAR_DURATION; 3];
+        staggered.update_child_count(&Vector::from(vec![1, 2, 3, 4, 5]), &env);
+        let d = APPEAR_DURATION;
+        assert!(close(&staggered.appear, &[d, d, d, 0., -0.1]));
+    }
+
+    #[test]
+    fn announcements_count_the_change() {
+        let announced = |old_len, len| announcement(old_len, len).unwrap();
+        assert_eq!(announced(2, 5), "3 items added");
+        assert_eq!(announced(5, 4), "1 item removed");
+        assert_eq!(announced(0, 1), "1 item added");
+        assert_eq!(announced(7, 0), "7 items removed");
+    }
+
+    #[test]
+    fn items_are_built_by_kind() {
+        let built = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut builders: HashMap<u32, ItemBuilder<u32>> = HashMap::new();
+        for kind in 1..3 {
+            let built = built.clone();
+            builders.insert(
+                kind,
+                Box::new(move || {
+                    built.borrow_mut().push(kind);
+                    Box::new(SizedBox::empty())
+                }),
+            );
+        }
+        let mut mixed = grid().with_builders(builders);
+        let mut data = Items::new(3);
+        data.kinds = Vector::from(vec![1, 2, 1]);
+        let env = Env::empty();
+        assert!(mixed.update_child_count(&data, &env));
+        assert_eq!(*built.borrow(), vec![1, 2, 1]);
+
+        // a changed kind rebuilds the item, but the count stays the same
+        built.borrow_mut().clear();
+        data.kinds = Vector::from(vec![1, 1, 1]);
+        assert!(mixed.update_child_count(&data, &env));
+        assert_eq!(*built.borrow(), vec![1]);
+        assert_eq!(mixed.kinds, vec![1, 1, 1]);
+        assert_eq!(announcement(3, mixed.children.len()), None);
+    }
+
+    /// Passes everything on to the grid it wraps, and records the item and
+    /// center of the grid's ripple after each event.
+    struct RippleProbe {
+        grid: GridView<u32, Vector<u32>>,
+        ripple: Rc<Cell<Option<(usize, Point)>>>,
+    }
+
+    impl Widget<Vector<u32>> for RippleProbe {
+        fn event(
+            &mut self,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Vector<u32>,
+            env: &Env,
+        ) {
+            self.grid.event(ctx, event, data, env);
+            let ripple = self.grid.ripple.as_ref();
+            self.ripple
+                .set(ripple.map(|ripple| (ripple.idx, ripple.center)));
+        }
+
+        fn lifecycle(
+            &mut self,
+            ctx: &mut LifeCycleCtx,
+            event: &LifeCycle,
+            data: &Vector<u32>,
+            env: &Env,
+        ) {
+            self.grid.lifecycle(ctx, event, data, env);
+        }
+
+        fn update(
+            &mut self,
+            ctx: &mut UpdateCtx,
+            old_data: &Vector<u32>,
+            data: &Vector<u32>,
+            env: &Env,
+        ) {
+            self.grid.update(ctx, old_data, data, env);
+        }
+
+        fn layout(
+            &mut self,
+            ctx: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            data: &Vector<u32>,
+            env: &Env,
+        ) -> Size {
+            self.grid.layout(ctx, bc, data, env)
+        }
+
+        fn paint(&mut self, ctx: &mut PaintCtx, data: &Vector<u32>, env: &Env) {
+            self.grid.paint(ctx, data, env);
+        }
+    }
+
+    #[test]
+    fn enter_starts_a_ripple_at_the_center_of_the_item() {
+        let ripple = Rc::new(Cell::new(None));
+        let (grid, _) = sized_grid();
+        let probe = RippleProbe {
+            grid: grid.with_cell_ripple(Color::WHITE),
+            ripple: ripple.clone(),
+        };
+        let root = Align::new(UnitPoint::TOP_LEFT, probe);
+        let data: Vector<u32> = (0..6).collect();
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            harness.just_layout();
+            // a click focuses item 2 and ripples from the mouse
+            let pos = Point::new(21., 1.);
+            harness.event(Event::MouseDown(left_mouse(pos, MouseButton::Left)));
+            harness.event(Event::MouseUp(left_mouse(pos, MouseButton::Left)));
+            assert_eq!(ripple.get(), Some((2, pos)));
+
+            harness.event(key_down(KbKey::Enter));
+            assert_eq!(ripple.get(), Some((2, Point::new(25., 5.))));
+        });
+    }
+
+    #[test]
+    fn ripple_grows_to_the_farthest_corner() {
+        let cell = Rect::new(0., 0., 80., 60.);
+        // keyboard activation starts the ripple at the center of the item
+        assert_eq!(ripple_reach(cell, cell.center()), 50.);
+        // a click near a corner has to reach the opposite one
+        assert_eq!(ripple_reach(cell, Point::ZERO), 100.);
+    }
+
+    #[test]
+    fn scroll_shadows_follow_the_scroll_position() {
+        let mut shaded = grid().with_scroll_shadows(Color::BLACK);
+        let size = Size::new(200., 1000.);
+        let shadows = |shaded: &GridView<u32, Vector<u32>>| {
+            shaded
+                .overflow_bands(size, SCROLL_SHADOW_DEPTH)
+                .into_iter()
+                .map(|(band, _, _)| band)
+                .collect::<Vec<_>>()
+        };
+
+        // halfway down there is more to see in both directions
+        shaded.viewport = Rect::new(0., 400., 200., 600.);
+        assert_eq!(
+            shadows(&shaded),
+            vec![
+                Rect::new(0., 400., 200., 406.),
+                Rect::new(0., 594., 200., 600.)
+            ]
+        );
+        // at the top only the bottom edge is shaded
+        shaded.viewport = Rect::new(0., 0., 200., 200.);
+        assert_eq!(shadows(&shaded), vec![Rect::new(0., 194., 200., 200.)]);
+        // and at the bottom only the top edge
+        shaded.viewport = Rect::new(0., 800., 200., 1000.);
+        assert_eq!(shadows(&shaded), vec![Rect::new(0., 800., 200., 806.)]);
+
+        let mut sideways =
+            grid().horizontal().with_scroll_shadows(Color::BLACK);
+        sideways.viewport = Rect::new(0., 0., 200., 1000.);
+        let wide = Size::new(600., 1000.);
+        let bands = sideways.overflow_bands(wide, SCROLL_SHADOW_DEPTH);
+        assert_eq!(bands.len(), 1);
+        assert_eq!(bands[0].0, Rect::new(194., 0., 200., 1000.));
+    }
+
+    #[test]
+    fn long_press_enters_selection_mode() {
+        let mut touch =
+            grid().with_long_press_selection(Duration::from_millis(500));
+        assert!(!touch.in_selection_mode());
+        touch.pressed = Some(1);
+
+        let selection = touch.long_pressed(1);
+        assert!(touch.in_selection_mode());
+        assert_eq!(selection, Some(vec![1]));
+        assert_eq!(touch.pressed, None);
+        touch.selection = vec![1];
+
+        // once in selection mode presses toggle items instead
+        assert_eq!(touch.toggled(3), vec![1, 3]);
+        touch.selection = vec![1, 3];
+        assert_eq!(touch.toggled(1), vec![3]);
+        // long-pressing a selected item leaves it selected
+        assert_eq!(touch.long_pressed(3), None);
+    }
+
+    #[test]
+    fn small_cells_have_a_larger_touch_target() {
+        // 20x20 icons 30 apart
+        let rects = [Rect::new(0., 0., 20., 20.), Rect::new(30., 0., 50., 20.)];
+        let tap = |pos, target| hit_test(rects.iter().copied(), pos, target);
+        let target = Size::new(44., 44.);
+
+        assert_eq!(tap(Point::new(10., 10.), target), Some(0));
+        // just outside the first icon's visual
+        assert_eq!(tap(Point::new(10., 28.), Size::ZERO), None);
+        assert_eq!(tap(Point::new(10., 28.), target), Some(0));
+        // between the icons the closer one wins
+        assert_eq!(tap(Point::new(24., 10.), target), Some(0));
+        assert_eq!(tap(Point::new(26., 10.), target), Some(1));
+        // past the expanded target
+        assert_eq!(tap(Point::new(10., 40.), target), None);
+    }
+
+    /// Records the [`CONTENT_METRICS`] it receives.
+    struct MetricsProbe(Rc<RefCell<Vec<ContentMetrics>>>);
+
+    impl<T, W: Widget<T>> Controller<T, W> for MetricsProbe {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut T,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(CONTENT_METRICS) => {
+                    let metrics = *cmd.get_unchecked(CONTENT_METRICS);
+                    self.0.borrow_mut().push(metrics);
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    #[test]
+    fn content_metrics_follow_the_scroll() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let scrollbar = WidgetId::next();
+        let (grid, _) = sized_grid();
+        let grid = grid.with_content_metrics(scrollbar);
+        // a scrollbar next to the `Scroll`, which is 50x50
+        let root = Flex::row()
+            .with_flex_child(Scroll::new(grid).vertical(), 1.)
+            .with_child(
+                SizedBox::empty()
+                    .fix_width(10.)
+                    .controller(MetricsProbe(received.clone()))
+                    .with_id(scrollbar),
+            );
+        let data: Vector<u32> = (0..100).collect();
+        let last = || *received.borrow().last().unwrap();
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(60., 50.));
+            harness.send_initial_events();
+            layout_and_run(harness);
+            // 20 rows of 5
+            let metrics = ContentMetrics {
+                content_size: Size::new(50., 200.),
+                viewport_size: Size::new(50., 50.),
+                scroll_offset: Vec2::ZERO,
+            };
+            assert_eq!(*received.borrow(), [metrics]);
+
+            let mut wheel = left_mouse(Point::new(25., 25.), MouseButton::None);
+            wheel.wheel_delta = Vec2::new(0., 60.);
+            harness.event(Event::Wheel(wheel));
+            let scrolled = ContentMetrics {
+                scroll_offset: Vec2::new(0., 60.),
+                ..metrics
+            };
+            assert_eq!(last(), scrolled);
+        });
+    }
+
+    #[test]
+    fn entering_items_slide_up_from_below() {
+        let sliding = grid().with_entrance(Entrance::FromBottom);
+        let entrance = sliding.entrance.unwrap();
+        let cell = Rect::new(0., 100., 50., 140.);
+        let origin_at =
+            |progress| entrance.transform(cell, progress) * cell.origin();
+
+        // one item height below where it ends up
+        assert_eq!(origin_at(0.), Point::new(0., 140.));
+        let halfway = origin_at(0.5);
+        assert!(halfway.y > 100. && halfway.y < 140.);
+        assert!(origin_at(0.75).y < halfway.y);
+        assert_eq!(origin_at(1.), cell.origin());
+        assert_eq!(entrance.transform(cell, 1.), Affine::IDENTITY);
+
+        let scaled = Entrance::Scale.transform(cell, 0.);
+        assert_eq!(scaled * cell.origin(), cell.center());
+    }
+
+    type Counted = (usize, Vector<u32>);
+
+    #[test]
+    fn column_count_is_written_once_per_change() {
+        let env = Env::empty();
+        let counting = GridView::<(usize, u32), _>::new(SizedBox::empty);
+        let mut counting =
+            counting.wrap().with_column_count_sink(lens!(Counted, 0));
+        counting.cell_size = Size::new(50., 50.);
+        let mut shown = (0, Vector::new());
+
+        // the container being resized, as seen by layout
+        let resize = |counting: &mut GridView<_, Counted>, width| {
+            let count = counting.preferred_columns(width, &env);
+            counting.set_column_count(count);
+        };
+        resize(&mut counting, 200.);
+        counting.write_column_count(&mut shown);
+        assert_eq!(shown.0, 4);
+        // the update caused by the write doesn't write again
+        shown.0 = 0;
+        resize(&mut counting, 200.);
+        counting.write_column_count(&mut shown);
+        assert_eq!(shown.0, 0);
+
+        resize(&mut counting, 120.);
+        counting.write_column_count(&mut shown);
+        assert_eq!(shown.0, 2);
+    }
+
+    #[test]
+    fn flow_mode_counts_the_fullest_line() {
+        assert_eq!(longest_line(&[]), 0);
+        assert_eq!(longest_line(&[0, 0, 0]), 3);
+        assert_eq!(longest_line(&[0, 0, 1, 1, 1, 1, 2]), 4);
+        assert_eq!(longest_line(&[0, 1, 2]), 1);
+    }
+
+    #[test]
+    fn resizing_writes_the_column_count_once() {
+        let (grid, _) = sized_grid::<(usize, u32), Counted>();
+        let grid = grid.wrap().with_column_count_sink(lens!(Counted, 0));
+        let requested = Rc::new(Cell::new(false));
+        let probe = LayoutProbe {
+            child: WidgetPod::new(grid),
+            requested: requested.clone(),
+        };
+        let root = Align::new(UnitPoint::TOP_LEFT, probe);
+        let data = (0, (0..12).collect());
+        Harness::create_simple(data, root, |harness| {
+            harness.set_initial_size(Size::new(50., 50.));
+            harness.send_initial_events();
+            layout_and_run(harness);
+            assert_eq!(harness.data().0, 5);
+            // the write doesn't ask for another layout
+            assert!(!requested.get());
+
+            harness.event(Event::WindowSize(Size::new(30., 50.)));
+            layout_and_run(harness);
+            assert_eq!(harness.data().0, 3);
+            assert!(!requested.get());
+        });
     }
 }